@@ -0,0 +1,215 @@
+//! Benchmarks comparing lock strategies for the list's endpoints.
+//!
+//! These are compiled only with the `bench` feature. Each benchmark spawns a configurable number
+//! of reader threads, which repeatedly read the head of a list, and writer threads, which
+//! repeatedly move the head of the list to the back.
+
+use std::collections::VecDeque;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::{poisoned, ArcNode, List, Node};
+
+/// Lock statistics collected by a [`List`], returned by [`List::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of times an operation had to start over because it failed to acquire a lock.
+    pub retries: u64,
+}
+
+/// The strategy used to protect the list during a benchmark.
+///
+/// Both strategies run the same workload over the same nodes: readers fetch the head, and
+/// writers pop the head and put it back at the tail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockStrategy {
+    /// A real [`List`], with its per-endpoint [`Mutex`](std::sync::Mutex)es and `try_lock`
+    /// retries. Readers call [`List::head`], and writers call [`List::pop_front`] and
+    /// [`List::put_back`].
+    Mutex,
+    /// A model of the list as a [`VecDeque`] of its nodes behind a single [`RwLock`].
+    ///
+    /// Since [`List`] has no `RwLock` mode, this stands in for one: readers share the lock to
+    /// fetch the front, and writers take it exclusively to move the front to the back. It never
+    /// retries, so it measures the cost of a coarse reader-writer lock as a baseline for the real
+    /// list.
+    RwLock,
+}
+
+/// The parameters of a benchmark run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// The number of threads reading the head of the list.
+    pub readers: usize,
+    /// The number of threads modifying the list.
+    pub writers: usize,
+    /// The number of operations each thread performs.
+    pub iterations: usize,
+}
+
+/// The results of a benchmark run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Report {
+    /// The strategy that was measured.
+    pub strategy: LockStrategy,
+    /// The total number of operations performed across all threads.
+    pub ops: usize,
+    /// The time taken to perform all the operations.
+    pub elapsed: Duration,
+    /// The lock statistics collected during the run.
+    ///
+    /// These are only collected for [`LockStrategy::Mutex`], since the `RwLock` model doesn't
+    /// retry.
+    pub stats: Stats,
+}
+
+impl Report {
+    /// Returns the throughput of the run, in operations per second.
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Runs a benchmark with the given strategy and parameters.
+pub fn run(strategy: LockStrategy, config: &Config) -> Report {
+    let (elapsed, stats) = match strategy {
+        LockStrategy::Mutex => run_mutex(config),
+        LockStrategy::RwLock => (run_rwlock(config), Stats::default()),
+    };
+    Report {
+        strategy,
+        ops: (config.readers + config.writers) * config.iterations,
+        elapsed,
+        stats,
+    }
+}
+
+// lock order:
+//  as in `List::head`
+//  as in `List::pop_front`
+//  as in `List::put_back`
+fn run_mutex(config: &Config) -> (Duration, Stats) {
+    let list = List::new();
+    // one node per writer, so there is always something to move
+    let _nodes: Vec<_> = (0..config.writers.max(1))
+        .map(|i| list.push_back(i))
+        .collect();
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..config.readers {
+            scope.spawn(|| {
+                for _ in 0..config.iterations {
+                    std::hint::black_box(list.head());
+                }
+            });
+        }
+        for _ in 0..config.writers {
+            scope.spawn(|| {
+                for _ in 0..config.iterations {
+                    if let Some(node) = list.pop_front() {
+                        list.put_back(&node);
+                    }
+                }
+            });
+        }
+    });
+    (start.elapsed(), list.stats())
+}
+
+fn run_rwlock(config: &Config) -> Duration {
+    let nodes: VecDeque<ArcNode<usize>> = (0..config.writers.max(1)).map(Node::new).collect();
+    let list = RwLock::new(nodes);
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..config.readers {
+            scope.spawn(|| {
+                for _ in 0..config.iterations {
+                    let head = list.read().unwrap_or_else(poisoned).front().cloned();
+                    std::hint::black_box(head);
+                }
+            });
+        }
+        for _ in 0..config.writers {
+            scope.spawn(|| {
+                for _ in 0..config.iterations {
+                    let mut list = list.write().unwrap_or_else(poisoned);
+                    if let Some(node) = list.pop_front() {
+                        list.push_back(node);
+                    }
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+/// Measures how long it takes for `threads` threads to each perform `ops` operations on a shared
+/// list.
+///
+/// The operations cycle between pushing a new node to the back, popping a node from the front,
+/// and putting a previously popped node back, so the threads contend on both ends of the list.
+/// This is intended for measuring the cost of the `try_lock` retry loops on a given machine, and
+/// for tracking regressions in it.
+// lock order:
+//  as in `List::push_back`
+//  as in `List::pop_front`
+//  as in `List::put_back`
+pub fn contention(threads: usize, ops: usize) -> Duration {
+    let list = List::new();
+    let start = Instant::now();
+    // every node stays alive until all the threads have finished, so that no thread can observe a
+    // dangling link
+    let _owned: Vec<Vec<_>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                let list = &list;
+                scope.spawn(move || {
+                    let mut owned = Vec::new();
+                    let mut popped = Vec::new();
+                    for op in 0..ops {
+                        match op % 3 {
+                            0 => owned.push(list.push_back(thread)),
+                            1 => popped.extend(list.pop_front()),
+                            _ => {
+                                if let Some(node) = popped.pop() {
+                                    list.put_back(&node);
+                                }
+                            }
+                        }
+                    }
+                    owned
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{contention, run, Config, LockStrategy};
+
+    #[test]
+    fn smoke() {
+        let config = Config {
+            readers: 2,
+            writers: 2,
+            iterations: 10,
+        };
+        for strategy in [LockStrategy::Mutex, LockStrategy::RwLock] {
+            let report = run(strategy, &config);
+            assert_eq!(report.strategy, strategy);
+            assert_eq!(report.ops, 40);
+            assert!(report.ops_per_sec() > 0.0);
+        }
+    }
+
+    #[test]
+    fn contention_smoke() {
+        assert!(contention(4, 100) > std::time::Duration::ZERO);
+    }
+}
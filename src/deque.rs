@@ -0,0 +1,90 @@
+//! A double-ended queue built on [`List`].
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::{ArcNode, ArcNodeExt, DataGuard, List, Node};
+
+/// A double-ended queue backed by a [`List`].
+///
+/// Like [`Queue`](crate::Queue), the deque keeps the handle of every node it contains, so values
+/// are only ever removed by popping them.
+pub struct Deque<T> {
+    list: Arc<List<T>>,
+    /// The handles of the nodes in `list`, in the same order.
+    nodes: VecDeque<ArcNode<T>>,
+}
+
+impl<T> Deque<T> {
+    /// Creates an empty deque.
+    pub fn new() -> Self {
+        Self {
+            list: List::new(),
+            nodes: VecDeque::new(),
+        }
+    }
+
+    /// Returns the number of values in the deque.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Checks whether the deque is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Adds a value to the front of the deque.
+    pub fn push_front(&mut self, data: T) {
+        self.nodes.push_front(self.list.push_front(data));
+    }
+
+    /// Adds a value to the back of the deque.
+    pub fn push_back(&mut self, data: T) {
+        self.nodes.push_back(self.list.push_back(data));
+    }
+
+    /// Removes the value at the front of the deque, returning it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.list.pop_front()?;
+        let owned = self.nodes.pop_front();
+        Some(into_data(node, owned))
+    }
+
+    /// Removes the value at the back of the deque, returning it.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.list.pop_back()?;
+        let owned = self.nodes.pop_back();
+        Some(into_data(node, owned))
+    }
+
+    /// Locks the value at the front of the deque, returning a guard which dereferences to it.
+    pub fn front(&self) -> Option<DataGuard<'_, T>> {
+        self.nodes.front().map(ArcNodeExt::data_guard)
+    }
+
+    /// Locks the value at the back of the deque, returning a guard which dereferences to it.
+    pub fn back(&self) -> Option<DataGuard<'_, T>> {
+        self.nodes.back().map(ArcNodeExt::data_guard)
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Takes the data out of a popped `node`, after dropping the owning collection's own handle to it.
+///
+/// This is shared with [`Queue`](crate::Queue), which keeps its handles the same way.
+pub(crate) fn into_data<T>(node: ArcNode<T>, owned: Option<ArcNode<T>>) -> T {
+    debug_assert!(owned
+        .as_ref()
+        .is_some_and(|owned| Arc::ptr_eq(owned, &node)));
+    drop(owned);
+    match Node::try_into_data(node) {
+        Ok(data) => data,
+        Err(_) => unreachable!("owned nodes are never shared"),
+    }
+}
@@ -0,0 +1,72 @@
+//! A lock-free cache of a list's head or tail pointer.
+
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::lock;
+
+/// A copy of a [`Weak`] pointer which can be upgraded without taking a lock.
+///
+/// Stores must be serialized externally; the list only calls [`store`](Self::store) while the
+/// `head` or `tail` it caches is locked. A [`load`](Self::load) returns either the value before
+/// or the value after any concurrent store, so it never observes a pointer that the locked path
+/// couldn't also have returned.
+pub(crate) struct HeadCache<T> {
+    /// A pointer created by [`Weak::into_raw`], which owns one weak reference.
+    ptr: AtomicPtr<T>,
+    /// The number of loads currently between reading `ptr` and upgrading it.
+    readers: AtomicUsize,
+    /// Pointers which have been replaced, but which may still be in use by a load.
+    ///
+    /// These are dropped by the first store which sees no loads in progress. A load which starts
+    /// after that point must read `ptr` after the replacement, so it can't be using any of them.
+    retired: Mutex<Vec<Weak<T>>>,
+}
+
+impl<T> HeadCache<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            ptr: AtomicPtr::new(Weak::into_raw(Weak::<T>::new()).cast_mut()),
+            readers: AtomicUsize::new(0),
+            retired: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Upgrades the cached pointer.
+    pub(crate) fn load(&self) -> Option<Arc<T>> {
+        self.readers.fetch_add(1, Ordering::SeqCst);
+        let ptr = self.ptr.load(Ordering::SeqCst);
+        // SAFETY: `ptr` came from `Weak::into_raw`, and the weak reference it owns is not dropped
+        // until a store sees `readers == 0`, which can't happen until this load decrements it.
+        // Wrapping it in `ManuallyDrop` leaves that reference owned by the cache.
+        let weak = ManuallyDrop::new(unsafe { Weak::from_raw(ptr) });
+        let upgraded = weak.upgrade();
+        self.readers.fetch_sub(1, Ordering::SeqCst);
+        upgraded
+    }
+
+    /// Replaces the cached pointer with a copy of `new`.
+    pub(crate) fn store(&self, new: &Weak<T>) {
+        let new = Weak::into_raw(Weak::clone(new)).cast_mut();
+        let old = self.ptr.swap(new, Ordering::SeqCst);
+        // SAFETY: `old` came from `Weak::into_raw` and has just been removed from the cache, so
+        // this is the only place that owns it.
+        let old = unsafe { Weak::from_raw(old) };
+        let mut retired = lock(&self.retired);
+        if self.readers.load(Ordering::SeqCst) == 0 {
+            // no load can still be using `old`, so it doesn't need to be retired at all
+            retired.clear();
+            drop(old);
+        } else {
+            retired.push(old);
+        }
+    }
+}
+
+impl<T> Drop for HeadCache<T> {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` came from `Weak::into_raw`, and nothing can load it any more.
+        drop(unsafe { Weak::from_raw(*self.ptr.get_mut()) });
+    }
+}
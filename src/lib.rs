@@ -1,203 +1,4604 @@
-use std::sync::{Arc, Mutex, MutexGuard, Weak};
+#[cfg(feature = "bench")]
+pub mod bench;
+mod deque;
+mod head_cache;
+mod queue;
+mod repair;
+
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError, Weak};
+
+pub use deque::Deque;
+use head_cache::HeadCache;
+pub use queue::Queue;
+pub use repair::{Issue, RepairReport};
+
+/// What [`Node::lock_with`] does when it finds a node's lock poisoned by a thread which panicked
+/// while holding it.
+///
+/// The policy is chosen per call, so one caller recovering a node can't change how any other
+/// code treats it. The list's own operations always panic on a poisoned lock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PoisonPolicy {
+    /// Panic, as `.lock().unwrap()` would.
+    #[default]
+    Panic,
+    /// Ignore the poison and carry on with the data as the panicking thread left it.
+    ///
+    /// The panic may have interrupted an operation halfway through relinking nodes, so a list
+    /// used this way should be checked with [`List::validate_and_repair`].
+    Recover,
+}
+
+impl PoisonPolicy {
+    /// Applies the policy to the guard of a poisoned lock.
+    fn apply<G>(self, err: PoisonError<G>) -> G {
+        match self {
+            PoisonPolicy::Panic => poisoned(err),
+            PoisonPolicy::Recover => err.into_inner(),
+        }
+    }
+}
+
+/// Panics on a poisoned lock.
+fn poisoned<G>(_: PoisonError<G>) -> G {
+    panic!("poisoned lock")
+}
+
+/// Helper function for `lock` which panics on a poisoned lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(poisoned)
+}
 
 /// Helper function for `try_lock` which panics on a poisoned lock.
-fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<T>> {
+fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
     match mutex.try_lock() {
         Ok(guard) => Some(guard),
-        Err(std::sync::TryLockError::Poisoned(_)) => {
-            panic!("poisoned lock");
+        Err(TryLockError::Poisoned(err)) => Some(poisoned(err)),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// An error from an operation on a [`Node`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeError {
+    /// The node belongs to a different list than the one the operation was called on.
+    ForeignList,
+}
+
+impl std::fmt::Display for NodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeError::ForeignList => f.write_str("node belongs to a different list"),
         }
-        Err(std::sync::TryLockError::WouldBlock) => None,
     }
 }
 
+impl std::error::Error for NodeError {}
+
 pub struct List<T> {
     head: Mutex<WeakNode<T>>,
+    /// A copy of `head` for lock-free reads, which is only updated while `head` is locked.
+    head_cache: HeadCache<Mutex<Node<T>>>,
     tail: Mutex<WeakNode<T>>,
+    /// A copy of `tail` for lock-free reads, which is only updated while `tail` is locked.
+    tail_cache: HeadCache<Mutex<Node<T>>>,
+    len: AtomicUsize,
+    /// Whether [`Node::next`] and [`Node::prev`] wrap around at the ends of the list.
+    circular: bool,
+    /// Handles kept alive by the list itself, for lists created with [`List::with_nodes`].
+    owned: Mutex<Vec<ArcNode<T>>>,
+    /// Detached nodes allocated ahead of time by [`List::reserve_nodes`].
+    pool: Mutex<Vec<ArcNode<T>>>,
+    #[cfg(feature = "async")]
+    len_tx: tokio::sync::watch::Sender<usize>,
+    #[cfg(feature = "bench")]
+    retries: std::sync::atomic::AtomicU64,
 }
 
 impl<T> List<T> {
     pub fn new() -> Arc<Self> {
+        Self::with_circular(false)
+    }
+
+    /// Creates an empty circular list.
+    ///
+    /// A circular list behaves like any other, except that [`Node::next`] on the tail returns the
+    /// head, and [`Node::prev`] on the head returns the tail, so walking a circular list by hand
+    /// never runs out of nodes; bound such walks by [`len`](Self::len). The list's own traversals,
+    /// such as [`iter`](Self::iter), still run once from the head to the tail, so combined with
+    /// [`rotate_nodes`](Self::rotate_nodes) they see the ring starting from any node.
+    pub fn new_circular() -> Arc<Self> {
+        Self::with_circular(true)
+    }
+
+    /// Creates a list containing only `data`, returning it along with the node's handle.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned node must be stored externally.
+    pub fn singleton(data: T) -> (Arc<Self>, ArcNode<T>) {
+        let list = Self::new();
+        let node = list.push_back(data);
+        (list, node)
+    }
+
+    /// Creates a list by moving each of `items` into a new node, returning it along with its nodes
+    /// in order.
+    pub fn from_vec(items: Vec<T>) -> (Arc<Self>, Vec<ArcNode<T>>) {
+        let list = Self::new();
+        let nodes = items.into_iter().map(|data| list.push_back(data)).collect();
+        (list, nodes)
+    }
+
+    /// Creates a list containing `data`, which keeps the handles of its nodes itself.
+    ///
+    /// Unlike a list built with [`push_back`](Self::push_back), the contents persist without the
+    /// caller storing any handles. The handles are kept until the list is dropped or they are
+    /// retrieved with [`take_handles`](Self::take_handles), even if the nodes are removed.
+    pub fn with_nodes(data: Vec<T>) -> Arc<Self> {
+        let list = Self::new();
+        let nodes = data.into_iter().map(|data| list.push_back(data)).collect();
+        *lock(&list.owned) = nodes;
+        list
+    }
+
+    /// Takes the handles kept by a list created with [`with_nodes`](Self::with_nodes).
+    ///
+    /// After this, the nodes are only kept alive by the returned handles, as for any other list.
+    /// Calling this again, or on any other list, returns an empty [`Vec`].
+    // lock order:
+    //  self.owned {}
+    pub fn take_handles(&self) -> Vec<ArcNode<T>> {
+        std::mem::take(&mut *lock(&self.owned))
+    }
+
+    /// Releases any memory the list is holding on to beyond its current contents.
+    ///
+    /// This frees any nodes left over from [`reserve_nodes`](Self::reserve_nodes). For a list
+    /// created with [`with_nodes`](Self::with_nodes), it also drops the kept handles of nodes
+    /// which have since been removed, freeing them unless they are referenced elsewhere.
+    // lock order:
+    //  self.pool {}
+    //  self.owned {
+    //    each owned node, one at a time {}
+    //  }
+    pub fn shrink_to_fit(self: &Arc<Self>) {
+        *lock(&self.pool) = Vec::new();
+        let mut owned = lock(&self.owned);
+        owned.retain(|node| lock(node).parent_is(self));
+        owned.shrink_to_fit();
+    }
+
+    /// Allocates `n` nodes ahead of time, so that the next `n` nodes created by the list don't
+    /// need to allocate.
+    ///
+    /// Reserved nodes hold [`T::default()`](Default::default) until they are used, at which point
+    /// that value is replaced and dropped. They are freed by
+    /// [`shrink_to_fit`](Self::shrink_to_fit), or when the list is dropped.
+    // lock order:
+    //  self.pool {}
+    pub fn reserve_nodes(&self, n: usize)
+    where
+        T: Default,
+    {
+        lock(&self.pool).extend((0..n).map(|_| Node::new(T::default())));
+    }
+
+    /// Creates a detached node holding `data`, reusing a reserved node if there is one.
+    // lock order:
+    //  self.pool {}
+    //  the reused node {}
+    fn new_node(&self, data: T) -> ArcNode<T> {
+        let reused = lock(&self.pool).pop();
+        match reused {
+            Some(node) => {
+                lock(&node).data = data;
+                node
+            }
+            None => Node::new(data),
+        }
+    }
+
+    fn with_circular(circular: bool) -> Arc<Self> {
         Arc::new(Self {
             head: Mutex::new(Weak::new()),
+            head_cache: HeadCache::new(),
             tail: Mutex::new(Weak::new()),
+            tail_cache: HeadCache::new(),
+            len: AtomicUsize::new(0),
+            circular,
+            owned: Mutex::new(Vec::new()),
+            pool: Mutex::new(Vec::new()),
+            #[cfg(feature = "async")]
+            len_tx: tokio::sync::watch::Sender::new(0),
+            #[cfg(feature = "bench")]
+            retries: std::sync::atomic::AtomicU64::new(0),
         })
     }
 
+    /// Returns the number of nodes in the list.
+    ///
+    /// If the list is being modified concurrently, the result may already be out of date.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Checks whether the list has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether the list was created with [`new_circular`](Self::new_circular).
+    pub fn is_circular(&self) -> bool {
+        self.circular
+    }
+
+    /// Subscribes to changes in the length of the list.
+    ///
+    /// The receiver is updated after every operation which changes the length, once the operation
+    /// has released its locks; moving a node within the list doesn't notify it. The exception is
+    /// [`Node::remove`], which notifies while the caller still holds the node's lock, so holding a
+    /// borrow of the receiver's value can block it, and it should be released promptly.
+    #[cfg(feature = "async")]
+    pub fn subscribe_len(&self) -> tokio::sync::watch::Receiver<usize> {
+        self.len_tx.subscribe()
+    }
+
+    /// Returns a future which completes once the list is empty.
+    ///
+    /// This waits on the same channel as [`subscribe_len`](Self::subscribe_len), so it completes
+    /// immediately if the list is already empty, and otherwise after the removal which empties it.
+    /// If nodes are pushed again straight away, the momentary empty state may be missed.
+    #[cfg(feature = "async")]
+    pub fn notify_when_empty(&self) -> impl std::future::Future<Output = ()> {
+        let mut len = self.subscribe_len();
+        async move {
+            // an error means the list was dropped, so it can't have any nodes left
+            let _ = len.wait_for(|&len| len == 0).await;
+        }
+    }
+
+    /// Returns the lock statistics collected for this list so far.
+    #[cfg(feature = "bench")]
+    pub fn stats(&self) -> bench::Stats {
+        bench::Stats {
+            retries: self.retries.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Records that an operation had to retry after failing to acquire a lock.
+    fn record_retry(&self) {
+        #[cfg(feature = "bench")]
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Locks `first`, then `second`, which must be different nodes.
+    ///
+    /// `second` is only attempted with `try_lock`, and on failure both are released and this
+    /// starts over, so it can't deadlock with an operation that holds `second` while locking
+    /// `first`, such as `Node::remove` locking a neighbor.
+    // lock order:
+    //  first {
+    //    second, try_lock {}
+    //  }
+    fn lock_pair<'a>(
+        &self,
+        first: &'a ArcNode<T>,
+        second: &'a ArcNode<T>,
+    ) -> (MutexGuard<'a, Node<T>>, MutexGuard<'a, Node<T>>) {
+        debug_assert!(!Arc::ptr_eq(first, second), "cannot lock a node twice");
+        loop {
+            let first_lock = lock(first);
+            if let Some(second_lock) = try_lock(second) {
+                return (first_lock, second_lock);
+            }
+            self.record_retry();
+        }
+    }
+
+    /// Counts a node which is being linked into this list after being unlinked from `old_parent`,
+    /// returning whether any length changed.
+    ///
+    /// This must be called before the node is unlocked, so that it can't be removed again before
+    /// it is counted. A node moving within this list doesn't change its length.
+    fn count_linked(&self, old_parent: Option<&Arc<List<T>>>) -> bool {
+        if old_parent.is_some_and(|old| std::ptr::eq(&**old, self)) {
+            return false;
+        }
+        if let Some(old) = old_parent {
+            old.len.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.len.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Publishes the lengths changed by [`count_linked`](Self::count_linked), if any.
+    ///
+    /// This must be called after every lock has been released.
+    fn notify_linked(&self, old_parent: Option<&Arc<List<T>>>, changed: bool) {
+        if changed {
+            if let Some(old) = old_parent {
+                old.notify_len();
+            }
+            self.notify_len();
+        }
+    }
+
+    /// Publishes the current length of the list to any subscribers.
+    ///
+    /// This must be called after any list or neighbor locks have been released.
+    fn notify_len(&self) {
+        #[cfg(feature = "async")]
+        self.len_tx.send_if_modified(|sent| {
+            // read the length under the channel's lock, so that racing updates can't be
+            // published out of order
+            let len = self.len();
+            std::mem::replace(sent, len) != len
+        });
+    }
+
     /// Pushes a new node to the back of the list, returning the created node.
     ///
     /// The returned [`ArcNode<T>`] has `strong_count == 1`, which means that if it is dropped, it
     /// will be removed from the list, so it is important to store all the returned nodes
     /// externally from the list itself
     pub fn push_back(self: &Arc<Self>, data: T) -> ArcNode<T> {
-        let new = Node::new(data);
+        let new = self.new_node(data);
         self.put_back(&new);
-        debug_assert_eq!(Arc::strong_count(&new), 1);
         new
     }
 
     // lock order:
     //  node {
     //    self.tail {
-    //      self.tail.tail? {}
-    //      self.head {}
+    //      self.tail.tail? {
+    //        self.head {}
+    //      }
     //    }
     //  }
     pub fn put_back(self: &Arc<Self>, node: &ArcNode<T>) {
-        let mut node_lock = node.lock().unwrap();
+        let mut node_lock = lock(node);
         // remove node from its current place
-        node_lock.remove();
-        loop {
-            let mut tail = self.tail.lock().unwrap();
+        let old_parent = node_lock.unlink();
+        self.put_back_locked(node, node_lock, old_parent);
+    }
+
+    /// Like [`put_back`](Self::put_back), but returns an error instead of moving `node` if it
+    /// currently belongs to a different list.
+    ///
+    /// Nodes which are unlinked or already in this list are put at the back as usual.
+    // lock order:
+    //  as in `put_back`
+    pub fn checked_put_back(self: &Arc<Self>, node: &ArcNode<T>) -> Result<(), NodeError> {
+        let mut node_lock = lock(node);
+        if node_lock.is_linked() && !node_lock.parent_is(self) {
+            return Err(NodeError::ForeignList);
+        }
+        let old_parent = node_lock.unlink();
+        self.put_back_locked(node, node_lock, old_parent);
+        Ok(())
+    }
+
+    /// Links `node`, which must be locked as `node_lock` and not in any list, as the new tail.
+    ///
+    /// `old_parent` is the list `node` was just unlinked from, if any, whose length still counts
+    /// it. Returns the previous tail.
+    fn put_back_locked(
+        self: &Arc<Self>,
+        node: &ArcNode<T>,
+        mut node_lock: MutexGuard<'_, Node<T>>,
+        old_parent: Option<Arc<List<T>>>,
+    ) -> Option<ArcNode<T>> {
+        let changed = loop {
+            let mut tail = lock(&self.tail);
+            let old_tail = tail.upgrade();
+            // call `try_lock` because otherwise we could deadlock with `Node::remove`
+            let old_tail_lock = match &old_tail {
+                Some(old_tail) => match try_lock(old_tail) {
+                    Some(lock) => Some(lock),
+                    None => {
+                        // we failed to get a lock on `tail`, try again from the top
+                        self.record_retry();
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            // `Node::remove` on the head locks `self.head` before the node after it, which may be
+            // `tail`, so this needs `try_lock` as well
+            let Some(mut head) = try_lock(&self.head) else {
+                self.record_retry();
+                continue;
+            };
+            // nothing is modified until every lock is held, so that no other thread can reach
+            // `node` through the list while we might still retry
             node_lock.parent = Arc::downgrade(self);
             node_lock.prev = Weak::clone(&tail);
-            if let Some(tail) = tail.upgrade() {
+            if let Some(mut tail_lock) = old_tail_lock {
                 // list isn't empty, need to update `tail`'s `next` pointer
+                tail_lock.next = Arc::downgrade(node);
+            } else {
+                // list is empty, need to set `head` as well
+                self.set_head(&mut head, Arc::downgrade(node));
+            }
+            drop(head);
+            // set `tail`
+            self.set_tail(&mut tail, Arc::downgrade(node));
+            break self.count_linked(old_parent.as_ref());
+        };
+        let old_tail = node_lock.prev.upgrade();
+        drop(node_lock);
+        self.notify_linked(old_parent.as_ref(), changed);
+        old_tail
+    }
+
+    /// Pushes a new node to the front of the list, returning the created node.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned node must be stored externally.
+    pub fn push_front(self: &Arc<Self>, data: T) -> ArcNode<T> {
+        let new = self.new_node(data);
+        self.put_front(&new);
+        new
+    }
+
+    // lock order:
+    //  node {
+    //    self.tail {
+    //      self.head {
+    //        self.head.head? {}
+    //      }
+    //    }
+    //  }
+    // `self.tail` is locked even when it isn't needed, since `Node::remove` and `put_back` lock
+    // `self.tail` before `self.head`.
+    pub fn put_front(self: &Arc<Self>, node: &ArcNode<T>) {
+        let mut node_lock = lock(node);
+        // remove node from its current place
+        let old_parent = node_lock.unlink();
+        self.put_front_locked(node, node_lock, old_parent, |_| true);
+    }
+
+    /// Links `node`, which must be locked as `node_lock` and not in any list, as the new head,
+    /// provided that `expected` returns `true` for the current head.
+    ///
+    /// `old_parent` is the list `node` was just unlinked from, if any, whose length still counts
+    /// it. Returns whether the node was linked.
+    // lock order:
+    //  as in `put_front`
+    fn put_front_locked<F: FnMut(&WeakNode<T>) -> bool>(
+        self: &Arc<Self>,
+        node: &ArcNode<T>,
+        mut node_lock: MutexGuard<'_, Node<T>>,
+        old_parent: Option<Arc<List<T>>>,
+        mut expected: F,
+    ) -> bool {
+        let changed = loop {
+            let mut tail = lock(&self.tail);
+            let mut head = lock(&self.head);
+            if !expected(&head) {
+                return false;
+            }
+            node_lock.parent = Arc::downgrade(self);
+            node_lock.next = Weak::clone(&head);
+            if let Some(head) = head.upgrade() {
+                // list isn't empty, need to update `head`'s `prev` pointer
                 // call `try_lock` because otherwise we could deadlock with `Node::remove`
-                if let Some(mut tail_lock) = try_lock(&tail) {
-                    tail_lock.next = Arc::downgrade(&node);
+                if let Some(mut head_lock) = try_lock(&head) {
+                    head_lock.prev = Arc::downgrade(node);
                 } else {
-                    // we failed to get a lock on `tail`, try again from the top
+                    // we failed to get a lock on `head`, try again from the top
+                    self.record_retry();
                     continue;
                 }
+            } else {
+                // list is empty, need to set `tail` as well
+                self.set_tail(&mut tail, Arc::downgrade(node));
             }
-            let mut head = self.head.lock().unwrap();
-            if head.upgrade().is_none() {
-                // list is empty, need to set `head` as well
-                *head = Arc::downgrade(&node);
-            }
-            drop(head);
-            // set `tail`
-            *tail = Arc::downgrade(&node);
-            break;
-        }
+            // set `head`
+            self.set_head(&mut head, Arc::downgrade(node));
+            break self.count_linked(old_parent.as_ref());
+        };
+        drop(node_lock);
+        self.notify_linked(old_parent.as_ref(), changed);
+        true
     }
 
+    /// Links `new` as the head of the list, but only if the current head is `expected`, returning
+    /// whether it was linked.
+    ///
+    /// The comparison and the link happen under the head lock, so no other operation can change
+    /// the head in between. An `expected` of `None` matches only an empty list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new` is already in a list.
     // lock order:
-    //  self.head {}
-    pub fn head(&self) -> Option<ArcNode<T>> {
-        self.head.lock().unwrap().upgrade()
+    //  as in `put_front`
+    pub fn cas_head(self: &Arc<Self>, expected: Option<&ArcNode<T>>, new: &ArcNode<T>) -> bool {
+        let node_lock = lock(new);
+        assert!(!node_lock.is_linked(), "node is already in a list");
+        let expected = expected.map(Arc::as_ptr);
+        self.put_front_locked(new, node_lock, None, |head| {
+            head.upgrade().as_ref().map(Arc::as_ptr) == expected
+        })
     }
-}
 
-pub type ArcNode<T> = Arc<Mutex<Node<T>>>;
-type WeakNode<T> = Weak<Mutex<Node<T>>>;
+    /// Links `node` as the head of the list, returning the previous head.
+    ///
+    /// Unlike [`put_front`](Self::put_front), this doesn't first remove `node` from wherever it
+    /// is, so it must not be in any list, such as a node freshly created with [`Node::new`]. This
+    /// is only checked in debug builds.
+    // lock order:
+    //  as in `put_front`
+    pub fn link_front(self: &Arc<Self>, node: &ArcNode<T>) -> Option<ArcNode<T>> {
+        let node_lock = lock(node);
+        debug_assert!(!node_lock.is_linked(), "node is already in a list");
+        let mut old_head = None;
+        self.put_front_locked(node, node_lock, None, |head| {
+            old_head = head.upgrade();
+            true
+        });
+        old_head
+    }
 
-/// Takes a [`Weak<T>`] and [`upgrade`](Weak::upgrade)s it, leaving [`Weak::new()`] in it's place.
-fn take_weak<T>(ptr: &mut Weak<T>) -> Option<Arc<T>> {
-    std::mem::take(ptr).upgrade()
-}
+    /// Links `node` as the tail of the list, returning the previous tail.
+    ///
+    /// Unlike [`put_back`](Self::put_back), this doesn't first remove `node` from wherever it is,
+    /// so it must not be in any list, such as a node freshly created with [`Node::new`]. This is
+    /// only checked in debug builds.
+    // lock order:
+    //  as in `put_back`
+    pub fn link_back(self: &Arc<Self>, node: &ArcNode<T>) -> Option<ArcNode<T>> {
+        let node_lock = lock(node);
+        debug_assert!(!node_lock.is_linked(), "node is already in a list");
+        self.put_back_locked(node, node_lock, None)
+    }
 
-pub struct Node<T> {
-    pub data: T,
-    parent: Weak<List<T>>,
-    prev: WeakNode<T>,
-    next: WeakNode<T>,
-}
+    /// Inserts a new node immediately before `node`, returning the created node.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned node must be stored externally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` does not belong to this list.
+    // lock order:
+    //  node {}
+    //  then as in `link_before`
+    pub fn insert_before(self: &Arc<Self>, node: &ArcNode<T>, data: T) -> ArcNode<T> {
+        assert!(
+            self.contains_node(node),
+            "node does not belong to this list"
+        );
+        let new = self.new_node(data);
+        self.link_before(node, &new);
+        new
+    }
 
-impl<T> Node<T> {
-    pub fn new(data: T) -> ArcNode<T> {
-        Arc::new(Mutex::new(Node {
-            data,
-            parent: Weak::new(),
-            prev: Weak::new(),
-            next: Weak::new(),
-        }))
+    /// Inserts a new node immediately after `node`, returning the created node.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned node must be stored externally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` does not belong to this list.
+    // lock order:
+    //  node {}
+    //  then as in `link_after`
+    pub fn insert_after(self: &Arc<Self>, node: &ArcNode<T>, data: T) -> ArcNode<T> {
+        assert!(
+            self.contains_node(node),
+            "node does not belong to this list"
+        );
+        let new = self.new_node(data);
+        self.link_after(node, &new);
+        new
     }
 
+    /// Inserts a new node for each item of `data` immediately after `after`, keeping the items in
+    /// order, and returns the created nodes.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned nodes must be stored externally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `after` does not belong to this list.
     // lock order:
-    //  self (implicit) ({
-    //    self.parent.tail {
-    //      self.parent.head {}
-    //    }
-    //  }|{
-    //    self.parent.head {
-    //      self.next {}
-    //    }
-    //  }|{
-    //    self.parent.tail {
-    //      self.prev {}
-    //    }
-    //  }|{
-    //    self.prev {
-    //      self.next {}
-    //    }
-    //  })
-    /// Removes the node from its parent [`List`].
-    pub fn remove(&mut self) {
-        let parent = if let Some(parent) = take_weak(&mut self.parent) {
-            parent
-        } else {
-            // already not in a list
-            debug_assert!(self.prev.upgrade().is_none());
-            debug_assert!(self.next.upgrade().is_none());
+    //  after {}
+    //  then, for each item, as in `link_after`
+    pub fn insert_many_after<I: IntoIterator<Item = T>>(
+        self: &Arc<Self>,
+        after: &ArcNode<T>,
+        data: I,
+    ) -> Vec<ArcNode<T>> {
+        assert!(
+            self.contains_node(after),
+            "node does not belong to this list"
+        );
+        let mut target = Arc::clone(after);
+        data.into_iter()
+            .map(|data| {
+                let new = self.new_node(data);
+                self.link_after(&target, &new);
+                target = Arc::clone(&new);
+                new
+            })
+            .collect()
+    }
+
+    /// Moves `node` so that it comes immediately after `target`.
+    ///
+    /// If `node` already comes immediately after `target`, the list is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node does not belong to this list, or if they are the same node.
+    // lock order:
+    //  node {}
+    //  target {}
+    //  then as in `link_after`
+    pub fn move_after(self: &Arc<Self>, node: &ArcNode<T>, target: &ArcNode<T>) {
+        assert!(
+            self.contains_node(node) && self.contains_node(target),
+            "node does not belong to this list"
+        );
+        assert!(
+            !Arc::ptr_eq(node, target),
+            "cannot move a node after itself"
+        );
+        let next = next_of(target);
+        if next.is_some_and(|next| Arc::ptr_eq(&next, node)) {
             return;
-        };
-        match (take_weak(&mut self.prev), take_weak(&mut self.next)) {
-            (None, None) => {
-                // only element of list
-                let mut tail = parent.tail.lock().unwrap();
-                *parent.head.lock().unwrap() = Weak::new();
-                *tail = Weak::new();
+        }
+        self.link_after(target, node);
+    }
+
+    /// Inserts a new node so that it ends up at position `index`, returning the created node.
+    ///
+    /// Returns `None`, without inserting anything, if `index` is greater than the length of the
+    /// list.
+    // lock order:
+    //  each node up to index `index`, one at a time {}
+    //  then as in `insert_before` or `push_back`
+    pub fn insert_at(self: &Arc<Self>, index: usize, data: T) -> Option<ArcNode<T>> {
+        match self.iter().nth(index) {
+            Some(node) => Some(self.insert_before(&node, data)),
+            None if index == self.len() => Some(self.push_back(data)),
+            None => None,
+        }
+    }
+
+    /// Removes the node at position `index`, returning it, or `None` if `index` is out of range.
+    // lock order:
+    //  each node up to index `index`, one at a time {}
+    //  then that node (as in `Node::remove`)
+    pub fn remove_at(self: &Arc<Self>, index: usize) -> Option<ArcNode<T>> {
+        let node = self.iter().nth(index)?;
+        lock(&node).remove_unnotified();
+        self.notify_len();
+        Some(node)
+    }
+
+    /// Moves `node` to the front of the list, returning `false` if it was already the head.
+    ///
+    /// This is useful for LRU caches, where a hit on the most recently used entry doesn't need
+    /// any reordering.
+    // lock order:
+    //  self.head {}
+    //  then as in `put_front`
+    pub fn bump_to_front(self: &Arc<Self>, node: &ArcNode<T>) -> bool {
+        if self.head().is_some_and(|head| Arc::ptr_eq(&head, node)) {
+            return false;
+        }
+        self.put_front(node);
+        true
+    }
+
+    /// Rotates the list `n` places toward the front, so that the node `n` places after the head
+    /// becomes the new head.
+    ///
+    /// The head and tail pointers are advanced in a single step under the endpoint locks: the
+    /// nodes before the new head are spliced onto the back as a whole, so only the nodes at the
+    /// old and new ends are relinked, and the length never changes. Finding the new head takes
+    /// time proportional to `n` modulo the length. This is mostly useful for
+    /// [circular](Self::new_circular) lists, where it changes where traversals of the ring start,
+    /// but works on any list.
+    // lock order:
+    //  self.tail {
+    //    self.head {
+    //      each node up to the new head, one at a time, try_lock {}
+    //      the old and new head and tail, try_lock {}
+    //    }
+    //  }
+    pub fn rotate_nodes(self: &Arc<Self>, n: usize) {
+        'retry: loop {
+            let len = self.len();
+            if len == 0 {
+                return;
             }
-            (None, Some(next)) => {
-                // head of list
-                let mut head = parent.head.lock().unwrap();
-                let mut next_lock = next.lock().unwrap();
-                *head = Arc::downgrade(&next);
-                next_lock.prev = Weak::new();
+            let steps = n % len;
+            if steps == 0 {
+                return;
             }
-            (Some(prev), None) => {
-                // tail of list
-                let mut tail = parent.tail.lock().unwrap();
-                let mut prev_lock = prev.lock().unwrap();
-                *tail = Arc::downgrade(&prev);
-                prev_lock.next = Weak::new();
+            let mut tail = lock(&self.tail);
+            let mut head = lock(&self.head);
+            let (Some(old_head), Some(old_tail)) = (head.upgrade(), tail.upgrade()) else {
+                return;
+            };
+            // `Node::remove` locks a node before the endpoints, so every node has to be locked
+            // with `try_lock` while they are held
+            let mut new_tail = Arc::clone(&old_head);
+            for _ in 1..steps {
+                let next = try_lock(&new_tail).map(|lock| lock.next.upgrade());
+                let Some(Some(next)) = next else {
+                    // a node is locked, or the list has shrunk since its length was read
+                    self.record_retry();
+                    continue 'retry;
+                };
+                new_tail = next;
             }
-            (Some(prev), Some(next)) => {
-                // middle of list, don't need to lock `parent`
-                let mut prev_lock = prev.lock().unwrap();
-                let mut next_lock = next.lock().unwrap();
-                prev_lock.next = Arc::downgrade(&next);
-                next_lock.prev = Arc::downgrade(&prev);
+            let new_head = try_lock(&new_tail).and_then(|lock| lock.next.upgrade());
+            let Some(new_head) = new_head else {
+                self.record_retry();
+                continue 'retry;
+            };
+
+            // the old and new ends may be the same nodes, and each must only be locked once
+            let mut ends: Vec<&ArcNode<T>> = Vec::with_capacity(4);
+            for node in [&old_head, &old_tail, &new_head, &new_tail] {
+                if !ends.iter().any(|end| Arc::ptr_eq(end, node)) {
+                    ends.push(node);
+                }
             }
+            let guards: Option<Vec<_>> = ends.iter().map(|node| try_lock(node)).collect();
+            let Some(mut guards) = guards else {
+                self.record_retry();
+                continue 'retry;
+            };
+            let at =
+                |node: &ArcNode<T>| ends.iter().position(|end| Arc::ptr_eq(end, node)).unwrap();
+            // with every end locked, check that `new_tail` is still just before `new_head`
+            let unchanged = guards.iter().all(|guard| guard.parent_is(self))
+                && guards[at(&new_tail)]
+                    .next
+                    .upgrade()
+                    .is_some_and(|next| Arc::ptr_eq(&next, &new_head));
+            if !unchanged {
+                self.record_retry();
+                continue 'retry;
+            }
+            guards[at(&old_tail)].next = Arc::downgrade(&old_head);
+            guards[at(&old_head)].prev = Arc::downgrade(&old_tail);
+            guards[at(&new_tail)].next = Weak::new();
+            guards[at(&new_head)].prev = Weak::new();
+            self.set_head(&mut head, Arc::downgrade(&new_head));
+            self.set_tail(&mut tail, Arc::downgrade(&new_tail));
+            return;
         }
     }
 
-    /// Retrieves the node after this one.
+    /// Retrieves the node at the front of the list.
+    ///
+    /// This first tries a lock-free read of a cached copy of the head pointer, which is kept in
+    /// sync with the head while it is locked, and only locks the head if that fails. Either way,
+    /// the result is a node which was the head at some point during the call.
     // lock order:
-    //  self (implicit) {}
-    pub fn next(&self) -> Option<ArcNode<T>> {
-        self.next.upgrade()
+    //  self.head? {}
+    pub fn head(&self) -> Option<ArcNode<T>> {
+        self.head_cache
+            .load()
+            .or_else(|| lock(&self.head).upgrade())
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::List;
+    /// Replaces the value of `self.head`, which must be locked as `head`, keeping the cached copy
+    /// in sync.
+    fn set_head(&self, head: &mut WeakNode<T>, new: WeakNode<T>) {
+        self.head_cache.store(&new);
+        *head = new;
+    }
 
-    #[test]
+    /// Replaces the value of `self.tail`, which must be locked as `tail`, keeping the cached copy
+    /// in sync.
+    fn set_tail(&self, tail: &mut WeakNode<T>, new: WeakNode<T>) {
+        self.tail_cache.store(&new);
+        *tail = new;
+    }
+
+    // lock order:
+    //  self.tail {}
+    pub fn tail(&self) -> Option<ArcNode<T>> {
+        lock(&self.tail).upgrade()
+    }
+
+    /// Retrieves the nodes at both ends of the list, as `(head, tail)`.
+    ///
+    /// Both ends are read while holding both endpoint locks, so they come from the same moment:
+    /// either both are `None`, or both are `Some`, and for a list with one element they are the
+    /// same node.
+    ///
+    /// The tail is locked before the head, rather than head then tail, since that is the order
+    /// every other operation which holds both ends uses, such as [`put_back`](Self::put_back) and
+    /// [`put_front`](Self::put_front); locking them the other way round could deadlock with those.
+    // lock order:
+    //  self.tail {
+    //    self.head {}
+    //  }
+    pub fn ends(&self) -> (Option<ArcNode<T>>, Option<ArcNode<T>>) {
+        let tail = lock(&self.tail);
+        let head = lock(&self.head);
+        (head.upgrade(), tail.upgrade())
+    }
+
+    /// Rotates the list one place at a time until the data at the head satisfies `f`, returning
+    /// the new head.
+    ///
+    /// If no node satisfies `f` after a full rotation, the list is back in its original order and
+    /// this returns `None`.
+    // lock order:
+    //  for each step {
+    //    self.head {}
+    //    head {}
+    //    then as in `rotate_nodes`
+    //  }
+    pub fn rotate_until<F: FnMut(&T) -> bool>(self: &Arc<Self>, mut f: F) -> Option<ArcNode<T>> {
+        for _ in 0..self.len() {
+            let head = self.head()?;
+            let found = f(&lock(&head).data);
+            if found {
+                return Some(head);
+            }
+            self.rotate_nodes(1);
+        }
+        None
+    }
+
+    /// Finds the first node from the head whose data satisfies `pred`, moves it to the back of the
+    /// list, and returns it.
+    ///
+    /// Since the selected node goes to the back, repeated calls cycle fairly through all of the
+    /// eligible nodes, like a round-robin scheduler. If no node is eligible, the list is left
+    /// unchanged and this returns `None`.
+    // lock order:
+    //  each node, one at a time {}
+    //  then as in `put_back`
+    pub fn next_eligible<F: FnMut(&T) -> bool>(
+        self: &Arc<Self>,
+        mut pred: F,
+    ) -> Option<ArcNode<T>> {
+        let node = self.iter().find(|node| pred(&lock(node).data))?;
+        self.put_back(&node);
+        Some(node)
+    }
+
+    /// Returns a clone of the data at the head of the list.
+    // lock order:
+    //  self.head {}
+    //  head {}
+    pub fn head_data(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let head = self.head()?;
+        let data = lock(&head).data.clone();
+        Some(data)
+    }
+
+    /// Returns a clone of the data at the tail of the list.
+    // lock order:
+    //  self.tail {}
+    //  tail {}
+    pub fn tail_data(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let tail = self.tail()?;
+        let data = lock(&tail).data.clone();
+        Some(data)
+    }
+
+    /// Removes the node at the front of the list, returning it.
+    // lock order:
+    //  self.head {}
+    //  head (as in `Node::remove`)
+    pub fn pop_front(self: &Arc<Self>) -> Option<ArcNode<T>> {
+        loop {
+            let head = self.head()?;
+            let mut head_lock = lock(&head);
+            if !head_lock.parent_is(self) || head_lock.prev.upgrade().is_some() {
+                // another thread moved or removed `head` before we locked it
+                self.record_retry();
+                continue;
+            }
+            head_lock.remove_unnotified();
+            drop(head_lock);
+            self.notify_len();
+            return Some(head);
+        }
+    }
+
+    /// Removes the node at the front of the list, returning it along with the list, which now
+    /// holds the rest of the nodes.
+    ///
+    /// Returns `None` if the list is empty.
+    // lock order:
+    //  as in `pop_front`
+    pub fn uncons(self: &Arc<Self>) -> Option<(ArcNode<T>, Arc<List<T>>)> {
+        let head = self.pop_front()?;
+        Some((head, Arc::clone(self)))
+    }
+
+    /// Removes the node at the front of the list and returns it, but only if `f` returns `true`
+    /// for its data.
+    ///
+    /// If `f` returns `false`, the list is left unchanged and this returns `None`.
+    // lock order:
+    //  self.head {}
+    //  head (as in `Node::remove`)
+    pub fn pop_front_if<F: FnOnce(&T) -> bool>(self: &Arc<Self>, f: F) -> Option<ArcNode<T>> {
+        loop {
+            let head = self.head()?;
+            let mut head_lock = lock(&head);
+            if !head_lock.parent_is(self) || head_lock.prev.upgrade().is_some() {
+                // another thread moved or removed `head` before we locked it
+                self.record_retry();
+                continue;
+            }
+            if !f(&head_lock.data) {
+                return None;
+            }
+            head_lock.remove_unnotified();
+            drop(head_lock);
+            self.notify_len();
+            return Some(head);
+        }
+    }
+
+    /// Removes the node at the back of the list, returning it.
+    // lock order:
+    //  self.tail {}
+    //  tail (as in `Node::remove`)
+    pub fn pop_back(self: &Arc<Self>) -> Option<ArcNode<T>> {
+        loop {
+            let tail = self.tail()?;
+            let mut tail_lock = lock(&tail);
+            if !tail_lock.parent_is(self) || tail_lock.next.upgrade().is_some() {
+                // another thread moved or removed `tail` before we locked it
+                self.record_retry();
+                continue;
+            }
+            tail_lock.remove_unnotified();
+            drop(tail_lock);
+            self.notify_len();
+            return Some(tail);
+        }
+    }
+
+    /// Creates an iterator which removes nodes from the front of the list, yielding them.
+    ///
+    /// If the iterator is dropped before it is exhausted, the remaining nodes are still removed.
+    pub fn drain(self: &Arc<Self>) -> Drain<T> {
+        Drain {
+            list: Arc::clone(self),
+        }
+    }
+
+    /// Creates an iterator which removes nodes from the back of the list, yielding them.
+    ///
+    /// If the iterator is dropped before it is exhausted, the remaining nodes are still removed.
+    pub fn drain_rev(self: &Arc<Self>) -> DrainRev<T> {
+        DrainRev {
+            list: Arc::clone(self),
+        }
+    }
+
+    /// Removes every node from the list, returning them in order.
+    ///
+    /// The returned nodes are no longer linked to the list or to each other.
+    // lock order:
+    //  as in `pop_front`, repeatedly
+    pub fn into_node_vec(self: &Arc<Self>) -> Vec<ArcNode<T>> {
+        std::iter::from_fn(|| self.pop_front()).collect()
+    }
+
+    /// Clones each element of `items` into a new node at the back of the list, returning the
+    /// created nodes in order.
+    ///
+    /// As with [`push_back`](Self::push_back), the returned nodes must be stored externally.
+    pub fn append_slice(self: &Arc<Self>, items: &[T]) -> Vec<ArcNode<T>>
+    where
+        T: Clone,
+    {
+        items
+            .iter()
+            .map(|item| self.push_back(item.clone()))
+            .collect()
+    }
+
+    /// Clones each element of `items` into a new node at the front of the list, returning the
+    /// created nodes in order.
+    ///
+    /// The elements keep their relative order, so `items[0]` becomes the new head.
+    pub fn prepend_slice(self: &Arc<Self>, items: &[T]) -> Vec<ArcNode<T>>
+    where
+        T: Clone,
+    {
+        let Some(head) = self.head() else {
+            return self.append_slice(items);
+        };
+        items
+            .iter()
+            .map(|item| {
+                let new = self.new_node(item.clone());
+                self.link_before(&head, &new);
+                new
+            })
+            .collect()
+    }
+
+    /// Pushes each item of `iter` to the front of the list in turn, returning the created nodes
+    /// in the order they were pushed.
+    ///
+    /// Since each item is pushed in front of the previous one, the batch ends up reversed: the
+    /// last item of `iter` becomes the new head. Use [`prepend_slice`](Self::prepend_slice) to keep
+    /// the items in order.
+    pub fn extend_front<I: IntoIterator<Item = T>>(self: &Arc<Self>, iter: I) -> Vec<ArcNode<T>> {
+        iter.into_iter().map(|data| self.push_front(data)).collect()
+    }
+
+    /// Moves each of `nodes` to the back of the list in turn, removing them from any list they are
+    /// currently in, and returns them in the same order.
+    ///
+    /// This is the counterpart to [`drain`](Self::drain), for reattaching removed nodes. The
+    /// handles are handed back so that the caller keeps the nodes alive.
+    // lock order:
+    //  for each node, as in `put_back`
+    pub fn extend_nodes<I: IntoIterator<Item = ArcNode<T>>>(
+        self: &Arc<Self>,
+        nodes: I,
+    ) -> Vec<ArcNode<T>> {
+        nodes
+            .into_iter()
+            .inspect(|node| self.put_back(node))
+            .collect()
+    }
+
+    /// Keeps the first `n` nodes of the list and removes the rest, returning the removed nodes in
+    /// order.
+    // lock order:
+    //  each node, one at a time {}
+    //  then each node after the first `n` (as in `Node::remove`)
+    pub fn retain_first_n(self: &Arc<Self>, n: usize) -> Vec<ArcNode<T>> {
+        let removed: Vec<_> = self.iter().skip(n).collect();
+        for node in &removed {
+            lock(node).remove_unnotified();
+        }
+        self.notify_len();
+        removed
+    }
+
+    /// Removes `node` and every node after it from the list, returning them in order.
+    ///
+    /// The node before `node`, if any, becomes the new tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` does not belong to this list.
+    // lock order:
+    //  node {}
+    //  each node from `node` onward, one at a time {}
+    //  then each of those nodes, from the tail backward (as in `Node::remove`)
+    pub fn drain_from(self: &Arc<Self>, node: &ArcNode<T>) -> Vec<ArcNode<T>> {
+        assert!(
+            self.contains_node(node),
+            "node does not belong to this list"
+        );
+        let drained: Vec<_> = Self::iter_from(node).collect();
+        // removing from the tail backward means each removal only touches one neighbor
+        for node in drained.iter().rev() {
+            lock(node).remove_unnotified();
+        }
+        self.notify_len();
+        drained
+    }
+
+    /// Moves the nodes of the list, in order, into new lists of `chunk` nodes each, leaving this
+    /// list empty.
+    ///
+    /// The last list may have fewer than `chunk` nodes. Existing handles to the nodes stay valid,
+    /// and now refer to nodes of the new lists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero.
+    // lock order:
+    //  for each node, as in `pop_front`, then as in `put_back`
+    pub fn split_into_chunks(self: &Arc<Self>, chunk: usize) -> Vec<Arc<List<T>>> {
+        assert!(chunk > 0, "chunk size must be non-zero");
+        let mut chunks = Vec::new();
+        while !self.is_empty() {
+            let list = List::new();
+            for node in std::iter::from_fn(|| self.pop_front()).take(chunk) {
+                list.put_back(&node);
+            }
+            chunks.push(list);
+        }
+        chunks
+    }
+
+    /// Removes every node whose data is equal to that of an earlier node, keeping the first
+    /// occurrence of each value.
+    ///
+    /// Unlike a consecutive dedup, duplicates are removed wherever they appear in the list.
+    // lock order:
+    //  each node, one at a time {}
+    //  then each duplicate node (as in `Node::remove`)
+    pub fn dedup_global(self: &Arc<Self>)
+    where
+        T: Hash + Eq + Clone,
+    {
+        let mut seen = HashSet::new();
+        for node in self.iter() {
+            let mut lock = lock(&node);
+            if !seen.insert(lock.data.clone()) {
+                lock.remove_unnotified();
+            }
+        }
+        self.notify_len();
+    }
+
+    /// Removes every node whose data is equal to that of an earlier node, keeping the first
+    /// occurrence of each value.
+    // lock order:
+    //  as in `dedup_global`
+    pub fn deduplicate(self: &Arc<Self>)
+    where
+        T: Hash + Eq + Clone,
+    {
+        self.dedup_global();
+    }
+
+    /// Removes every node whose data satisfies `pred`, returning clones of the removed data in
+    /// order.
+    // lock order:
+    //  each node, one at a time {}
+    //  then each matching node (as in `Node::remove`)
+    pub fn drain_where<F: FnMut(&T) -> bool>(self: &Arc<Self>, mut pred: F) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut drained = Vec::new();
+        for node in self.iter() {
+            let mut lock = lock(&node);
+            if pred(&lock.data) {
+                drained.push(lock.data.clone());
+                lock.remove_unnotified();
+            }
+        }
+        self.notify_len();
+        drained
+    }
+
+    /// Removes every node whose data does not satisfy `f`.
+    // lock order:
+    //  as in `filter_in_place`
+    pub fn retain<F: FnMut(&T) -> bool>(self: &Arc<Self>, f: F) {
+        self.filter_in_place(f);
+    }
+
+    /// Removes every node whose data does not satisfy `f`, returning the number of nodes removed.
+    // lock order:
+    //  each node, one at a time {}
+    //  then each removed node (as in `Node::remove`)
+    pub fn filter_in_place<F: FnMut(&T) -> bool>(self: &Arc<Self>, mut f: F) -> usize {
+        let mut removed = 0;
+        for node in self.iter() {
+            let mut lock = lock(&node);
+            if !f(&lock.data) {
+                lock.remove_unnotified();
+                removed += 1;
+            }
+        }
+        self.notify_len();
+        removed
+    }
+
+    /// Removes each of `nodes` from the list.
+    ///
+    /// The nodes are removed in order of address rather than the order given, so that concurrent
+    /// calls with overlapping batches take the node locks in a consistent order. Nodes which are
+    /// not in this list, including ones which appear twice in `nodes`, are skipped.
+    // lock order:
+    //  each node, in order of address (as in `Node::remove`)
+    pub fn remove_many(self: &Arc<Self>, nodes: &[ArcNode<T>]) {
+        let mut nodes: Vec<_> = nodes.iter().collect();
+        nodes.sort_by_key(|node| Arc::as_ptr(node));
+        nodes.dedup_by_key(|node| Arc::as_ptr(node));
+        for node in nodes {
+            let mut lock = lock(node);
+            if lock.parent_is(self) {
+                lock.remove_unnotified();
+            }
+        }
+        self.notify_len();
+    }
+
+    /// Merges adjacent elements of the list using `f`.
+    ///
+    /// For each pair of adjacent nodes, if `f` returns `Some(merged)`, the first node's data is
+    /// replaced with `merged` and the second node is removed. The merged node is then compared
+    /// with its new successor, so runs of mergeable elements collapse into a single node.
+    // lock order:
+    //  for each pair of adjacent nodes, as in `lock_pair`
+    //  then each merged node (as in `Node::remove`)
+    pub fn coalesce<F: FnMut(&T, &T) -> Option<T>>(self: &Arc<Self>, mut f: F) {
+        let Some(mut node) = self.head() else {
+            return;
+        };
+        while let Some(next) = next_of(&node) {
+            let (mut node_lock, next_lock) = self.lock_pair(&node, &next);
+            let merged = f(&node_lock.data, &next_lock.data);
+            drop(next_lock);
+            if let Some(merged) = merged {
+                node_lock.data = merged;
+                drop(node_lock);
+                lock(&next).remove_unnotified();
+            } else {
+                drop(node_lock);
+                node = next;
+            }
+        }
+        self.notify_len();
+    }
+
+    /// Creates an iterator which drains the list, moving each element out of its node.
+    ///
+    /// Any handles kept by the list itself, as for [`with_nodes`](Self::with_nodes), are taken by
+    /// the iterator, so an owning list yields all of its data. If a removed node is still
+    /// referenced elsewhere its data can't be moved out, so the node is yielded as an `Err`
+    /// instead.
+    ///
+    /// `Arc<List<T>>` can't implement [`IntoIterator`] directly, since neither the trait nor
+    /// [`Arc`] is defined in this crate (the orphan rule), so this is provided as an inherent
+    /// method: `for item in list.into_iter_data() { ... }`.
+    pub fn into_iter_data(self: Arc<Self>) -> IntoIterData<T> {
+        let owned = self
+            .take_handles()
+            .into_iter()
+            .map(|node| (Node::node_id(&node), node))
+            .collect();
+        IntoIterData { list: self, owned }
+    }
+
+    /// Checks whether `a` and `b` are handles to the same list.
+    pub fn same_list(a: &Arc<List<T>>, b: &Arc<List<T>>) -> bool {
+        Arc::ptr_eq(a, b)
+    }
+
+    /// Exchanges the contents of `a` and `b`, so that every node of `a` now belongs to `b`, and
+    /// vice versa.
+    ///
+    /// The endpoints of both lists are locked in order of address, so two threads swapping the
+    /// same pair of lists can't deadlock, whichever order they pass them in. Every node of both
+    /// lists is then locked before any of them is changed, so no other operation can see a node
+    /// whose parent doesn't match the list it was reached from. Handles kept by a list created
+    /// with [`with_nodes`](Self::with_nodes) move along with their nodes.
+    // lock order:
+    //  the list with the lower address: tail {
+    //    head {
+    //      the list with the higher address: tail {
+    //        head {
+    //          each node of either list, one at a time, try_lock {}
+    //          each node of either list, try_lock {}
+    //        }
+    //      }
+    //    }
+    //  }
+    //  then a.owned {
+    //    b.owned {}
+    //  }
+    pub fn swap_lists(a: &Arc<List<T>>, b: &Arc<List<T>>) {
+        if Self::same_list(a, b) {
+            return;
+        }
+        let (first, second) = if Arc::as_ptr(a) < Arc::as_ptr(b) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        'retry: loop {
+            let mut first_tail = lock(&first.tail);
+            let mut first_head = lock(&first.head);
+            let mut second_tail = lock(&second.tail);
+            let mut second_head = lock(&second.head);
+            // another operation may hold a node while waiting for an endpoint, so the nodes are
+            // only ever attempted with `try_lock`
+            let collect = |head: &WeakNode<T>| {
+                let mut nodes = Vec::new();
+                let mut next = head.upgrade();
+                while let Some(node) = next {
+                    next = try_lock(&node)?.next.upgrade();
+                    nodes.push(node);
+                }
+                Some(nodes)
+            };
+            let (Some(first_nodes), Some(second_nodes)) =
+                (collect(&first_head), collect(&second_head))
+            else {
+                a.record_retry();
+                continue 'retry;
+            };
+            let first_guards: Option<Vec<_>> =
+                first_nodes.iter().map(|node| try_lock(node)).collect();
+            let second_guards: Option<Vec<_>> =
+                second_nodes.iter().map(|node| try_lock(node)).collect();
+            let (Some(mut first_guards), Some(mut second_guards)) = (first_guards, second_guards)
+            else {
+                a.record_retry();
+                continue 'retry;
+            };
+            // with every node locked, the links can't change, so check that they still match
+            let ptr = |node: Option<ArcNode<T>>| node.as_ref().map(Arc::as_ptr);
+            let unchanged = |guards: &[MutexGuard<'_, Node<T>>], nodes: &[ArcNode<T>]| {
+                guards.iter().enumerate().all(|(i, guard)| {
+                    ptr(guard.next.upgrade()) == nodes.get(i + 1).map(Arc::as_ptr)
+                })
+            };
+            if !unchanged(&first_guards, &first_nodes) || !unchanged(&second_guards, &second_nodes)
+            {
+                a.record_retry();
+                continue 'retry;
+            }
+            // nothing is modified until every node is locked, so that retrying never leaves a node
+            // whose parent has already been changed, and the guards are held until the endpoints
+            // and lengths have been swapped as well
+            for guard in &mut first_guards {
+                guard.parent = Arc::downgrade(second);
+            }
+            for guard in &mut second_guards {
+                guard.parent = Arc::downgrade(first);
+            }
+            let new_first_head = std::mem::take(&mut *second_head);
+            let new_second_head = std::mem::replace(&mut *first_head, Weak::new());
+            first.set_head(&mut first_head, new_first_head);
+            second.set_head(&mut second_head, new_second_head);
+            let new_first_tail = std::mem::take(&mut *second_tail);
+            let new_second_tail = std::mem::replace(&mut *first_tail, Weak::new());
+            first.set_tail(&mut first_tail, new_first_tail);
+            second.set_tail(&mut second_tail, new_second_tail);
+            let first_len = first.len.load(Ordering::Relaxed);
+            let second_len = second.len.swap(first_len, Ordering::Relaxed);
+            first.len.store(second_len, Ordering::Relaxed);
+            break;
+        }
+        std::mem::swap(&mut *lock(&a.owned), &mut *lock(&b.owned));
+        a.notify_len();
+        b.notify_len();
+    }
+
+    /// Checks whether `node` belongs to this list.
+    // lock order:
+    //  node {}
+    pub fn contains_node(self: &Arc<Self>, node: &ArcNode<T>) -> bool {
+        let parent = lock(node).parent();
+        parent.is_some_and(|parent| Self::same_list(&parent, self))
+    }
+
+    /// Returns the position of `node` in this list, or `None` if it belongs to a different list.
+    ///
+    /// Membership is decided from the node's parent before walking back to the head with
+    /// [`Node::index`], so a foreign node costs no traversal at all.
+    // lock order:
+    //  node {}
+    //  then as in `Node::index`
+    pub fn rank(self: &Arc<Self>, node: &ArcNode<T>) -> Option<usize> {
+        if !self.contains_node(node) {
+            return None;
+        }
+        Node::index(node)
+    }
+
+    /// Links `node` into this list immediately after `target`, removing it from its current place
+    /// first.
+    ///
+    /// `target` must belong to this list, and must not be `node` itself.
+    // lock order:
+    //  node {
+    //    target {
+    //      target.next? {}
+    //      self.tail? {}
+    //    }
+    //  }
+    fn link_after(self: &Arc<Self>, target: &ArcNode<T>, node: &ArcNode<T>) {
+        debug_assert!(!Arc::ptr_eq(target, node));
+        let mut node_lock = lock(node);
+        // remove node from its current place
+        let old_parent = node_lock.unlink();
+        let changed = loop {
+            let mut target_lock = lock(target);
+            debug_assert!(target_lock.parent_is(self));
+            node_lock.parent = Arc::downgrade(self);
+            node_lock.prev = Arc::downgrade(target);
+            node_lock.next = Weak::clone(&target_lock.next);
+            if let Some(next) = target_lock.next.upgrade() {
+                // call `try_lock` because otherwise we could deadlock with `Node::remove`
+                if let Some(mut next_lock) = try_lock(&next) {
+                    next_lock.prev = Arc::downgrade(node);
+                } else {
+                    // we failed to get a lock on `next`, try again from the top
+                    self.record_retry();
+                    continue;
+                }
+            } else {
+                // `target` is the tail, so `node` becomes the new tail
+                self.set_tail(&mut lock(&self.tail), Arc::downgrade(node));
+            }
+            target_lock.next = Arc::downgrade(node);
+            break self.count_linked(old_parent.as_ref());
+        };
+        drop(node_lock);
+        self.notify_linked(old_parent.as_ref(), changed);
+    }
+
+    /// Links `node` into this list immediately before `target`, removing it from its current place
+    /// first.
+    ///
+    /// `target` must belong to this list, and must not be `node` itself.
+    // lock order:
+    //  node {
+    //    target {
+    //      target.prev? {}
+    //      self.head? {}
+    //    }
+    //  }
+    fn link_before(self: &Arc<Self>, target: &ArcNode<T>, node: &ArcNode<T>) {
+        debug_assert!(!Arc::ptr_eq(target, node));
+        let mut node_lock = lock(node);
+        // remove node from its current place
+        let old_parent = node_lock.unlink();
+        let changed = loop {
+            let mut target_lock = lock(target);
+            debug_assert!(target_lock.parent_is(self));
+            node_lock.parent = Arc::downgrade(self);
+            node_lock.next = Arc::downgrade(target);
+            node_lock.prev = Weak::clone(&target_lock.prev);
+            if let Some(prev) = target_lock.prev.upgrade() {
+                // call `try_lock` because otherwise we could deadlock with `Node::remove`
+                if let Some(mut prev_lock) = try_lock(&prev) {
+                    prev_lock.next = Arc::downgrade(node);
+                } else {
+                    // we failed to get a lock on `prev`, try again from the top
+                    self.record_retry();
+                    continue;
+                }
+            } else {
+                // `target` is the head, so `node` becomes the new head
+                self.set_head(&mut lock(&self.head), Arc::downgrade(node));
+            }
+            target_lock.prev = Arc::downgrade(node);
+            break self.count_linked(old_parent.as_ref());
+        };
+        drop(node_lock);
+        self.notify_linked(old_parent.as_ref(), changed);
+    }
+
+    /// Swaps the positions of the head and tail nodes, leaving any nodes between them in place.
+    ///
+    /// For a two-element list this reverses the list with a single relink. Lists with fewer than
+    /// two elements are left unchanged.
+    // lock order:
+    //  self.head {}
+    //  self.tail {}
+    //  head {}
+    //  then as in `link_after` or `link_before` and `put_back`
+    pub fn swap_head_tail(self: &Arc<Self>) {
+        let (Some(head), Some(tail)) = (self.head(), self.tail()) else {
+            return;
+        };
+        if Arc::ptr_eq(&head, &tail) {
+            return;
+        }
+        if next_of(&head).is_some_and(|next| Arc::ptr_eq(&next, &tail)) {
+            // only two elements
+            self.link_after(&tail, &head);
+        } else {
+            self.link_before(&head, &tail);
+            self.put_back(&head);
+        }
+    }
+
+    /// Swaps `node` with the node after it, returning `false` if `node` is the tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` does not belong to this list.
+    // lock order:
+    //  node {}
+    //  then as in `link_after`
+    pub fn swap_with_next(self: &Arc<Self>, node: &ArcNode<T>) -> bool {
+        let node_lock = lock(node);
+        assert!(
+            node_lock.parent_is(self),
+            "node does not belong to this list"
+        );
+        let next = node_lock.next.upgrade();
+        drop(node_lock);
+        if let Some(next) = next {
+            self.link_after(&next, node);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Swaps `node` with the node before it, returning `false` if `node` is the head.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` does not belong to this list.
+    // lock order:
+    //  node {}
+    //  then as in `link_before`
+    pub fn swap_with_prev(self: &Arc<Self>, node: &ArcNode<T>) -> bool {
+        let node_lock = lock(node);
+        assert!(
+            node_lock.parent_is(self),
+            "node does not belong to this list"
+        );
+        let prev = node_lock.prev.upgrade();
+        drop(node_lock);
+        if let Some(prev) = prev {
+            self.link_before(&prev, node);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reverses the order of the whole list.
+    // lock order:
+    //  self.head {}
+    //  self.tail {}
+    //  then as in `reverse_between`
+    pub fn reverse(self: &Arc<Self>) -> &Arc<Self> {
+        if let (Some(head), Some(tail)) = (self.head(), self.tail()) {
+            self.reverse_between(&head, &tail);
+        }
+        self
+    }
+
+    /// Reverses the segment of the list from `a` to `b`, inclusive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node does not belong to this list, or if `b` does not come after `a`.
+    // lock order:
+    //  each node in the segment, one at a time {}
+    //  then, for each node in the segment, the lock order of `link_after`
+    pub fn reverse_between(self: &Arc<Self>, a: &ArcNode<T>, b: &ArcNode<T>) {
+        let segment = self.segment(a, b);
+        let (last, rest) = segment.split_last().unwrap();
+        let mut target = last;
+        for node in rest.iter().rev() {
+            self.link_after(target, node);
+            target = node;
+        }
+    }
+
+    /// Reverses each consecutive group of `n` nodes in place, including the last group even if it
+    /// has fewer than `n` nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    // lock order:
+    //  as in `clone_handles`
+    //  then, for each group, as in `reverse_between`
+    pub fn reverse_in_chunks(self: &Arc<Self>, n: usize) {
+        assert!(n > 0, "chunk size must be non-zero");
+        for chunk in self.clone_handles().chunks(n) {
+            self.reverse_between(&chunk[0], chunk.last().unwrap());
+        }
+    }
+
+    /// Removes every node from `a` to `b`, inclusive, whose data does not satisfy `pred`.
+    ///
+    /// Nodes outside the segment are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node does not belong to this list, or if `b` does not come after `a`.
+    // lock order:
+    //  each node in the segment, one at a time {}
+    //  then each removed node (as in `Node::remove`)
+    pub fn retain_between<F: FnMut(&T) -> bool>(
+        self: &Arc<Self>,
+        a: &ArcNode<T>,
+        b: &ArcNode<T>,
+        mut pred: F,
+    ) {
+        for node in self.segment(a, b) {
+            let mut lock = lock(&node);
+            if !pred(&lock.data) {
+                lock.remove_unnotified();
+            }
+        }
+        self.notify_len();
+    }
+
+    /// Removes every node strictly between `a` and `b`, returning them in order.
+    ///
+    /// Afterward, `b` comes immediately after `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node does not belong to this list, or if `b` does not come after `a`.
+    // lock order:
+    //  each node in the segment, one at a time {}
+    //  then each removed node (as in `Node::remove`)
+    pub fn drain_between(self: &Arc<Self>, a: &ArcNode<T>, b: &ArcNode<T>) -> Vec<ArcNode<T>> {
+        assert!(!Arc::ptr_eq(a, b), "`b` does not come after `a`");
+        let mut segment = self.segment(a, b);
+        segment.pop();
+        segment.remove(0);
+        for node in &segment {
+            lock(node).remove_unnotified();
+        }
+        self.notify_len();
+        segment
+    }
+
+    /// Counts the nodes from `a` to `b`, inclusive.
+    ///
+    /// Returns `None` if `a` does not belong to this list, or if `b` does not come after it.
+    // lock order:
+    //  a {}
+    //  then as in `iter_from`
+    pub fn span_len(self: &Arc<Self>, a: &ArcNode<T>, b: &ArcNode<T>) -> Option<usize> {
+        if !self.contains_node(a) {
+            return None;
+        }
+        let index = Self::iter_from(a).position(|node| Arc::ptr_eq(&node, b))?;
+        Some(index + 1)
+    }
+
+    /// Collects the nodes from `a` to `b`, inclusive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node does not belong to this list, or if `b` does not come after `a`.
+    // lock order:
+    //  each node in the segment, one at a time {}
+    fn segment(self: &Arc<Self>, a: &ArcNode<T>, b: &ArcNode<T>) -> Vec<ArcNode<T>> {
+        assert!(
+            self.contains_node(a) && self.contains_node(b),
+            "node does not belong to this list"
+        );
+        let mut segment = vec![Arc::clone(a)];
+        while !Arc::ptr_eq(segment.last().unwrap(), b) {
+            let next = next_of(segment.last().unwrap()).expect("`b` does not come after `a`");
+            segment.push(next);
+        }
+        segment
+    }
+
+    /// Locks every node of the list at once and calls `f` with the guards, in list order.
+    ///
+    /// The locks are only ever attempted with `try_lock`, in order of address, so this never
+    /// blocks: if any node or either end of the list is already locked, every guard acquired so
+    /// far is released and this returns `None`. It also returns `None` if the list was modified
+    /// while the nodes were being locked, so within `f` the guards are a consistent snapshot of
+    /// the whole list.
+    // lock order:
+    //  self.head, try_lock {}
+    //  each node, one at a time, try_lock {}
+    //  each node, in order of address, try_lock {
+    //    self.tail, try_lock {
+    //      self.head, try_lock {}
+    //    }
+    //  }
+    pub fn try_with_all_locked<R, F: FnOnce(&[MutexGuard<'_, Node<T>>]) -> R>(
+        self: &Arc<Self>,
+        f: F,
+    ) -> Option<R> {
+        let mut nodes = Vec::new();
+        let mut next = try_lock(&self.head)?.upgrade();
+        while let Some(node) = next {
+            next = try_lock(&node)?.next.upgrade();
+            nodes.push(node);
+        }
+        let mut by_address: Vec<_> = nodes.iter().enumerate().collect();
+        by_address.sort_by_key(|(_, node)| Arc::as_ptr(node));
+        let mut guards = Vec::with_capacity(nodes.len());
+        for (i, node) in by_address {
+            guards.push((i, try_lock(node)?));
+        }
+        guards.sort_by_key(|(i, _)| *i);
+        let guards: Vec<_> = guards.into_iter().map(|(_, guard)| guard).collect();
+
+        // with every node locked, the links can't change, so check that they still match
+        let ptr = |node: Option<ArcNode<T>>| node.as_ref().map(Arc::as_ptr);
+        let (head, tail) = {
+            let tail = try_lock(&self.tail)?;
+            let head = try_lock(&self.head)?.upgrade();
+            (head, tail.upgrade())
+        };
+        let consistent = guards.iter().enumerate().all(|(i, guard)| {
+            guard.parent_is(self)
+                && ptr(guard.prev.upgrade()) == i.checked_sub(1).map(|i| Arc::as_ptr(&nodes[i]))
+                && ptr(guard.next.upgrade()) == nodes.get(i + 1).map(Arc::as_ptr)
+        }) && ptr(head) == nodes.first().map(Arc::as_ptr)
+            && ptr(tail) == nodes.last().map(Arc::as_ptr);
+        consistent.then(|| f(&guards))
+    }
+
+    /// Creates an iterator over the nodes of the list, from head to tail.
+    ///
+    /// Each node is only locked for long enough to find the node after it.
+    // lock order:
+    //  self.head {}
+    //  each node, one at a time {}
+    pub fn iter(&self) -> Iter<T> {
+        Iter {
+            next: self.head(),
+            remaining: self.len(),
+        }
+    }
+
+    /// Creates an iterator over clones of the data of the list, from head to tail.
+    ///
+    /// Each node is only locked for long enough to clone its data and find the node after it.
+    // lock order:
+    //  as in `iter`
+    pub fn iter_cloned(self: &Arc<Self>) -> impl Iterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.iter().map(|node| {
+            let data = lock(&node).data.clone();
+            data
+        })
+    }
+
+    /// Calls `f` on the data of the node at index `n`, returning its result, or `None` if the
+    /// list has `n` or fewer elements.
+    ///
+    /// The node is only locked for the duration of the call to `f`.
+    // lock order:
+    //  each node up to index `n`, one at a time {}
+    pub fn peek_nth<R, F: FnOnce(&T) -> R>(&self, n: usize, f: F) -> Option<R> {
+        let node = self.iter().nth(n)?;
+        let result = f(&lock(&node).data);
+        Some(result)
+    }
+
+    /// Calls `f` with a handle to each node of the list, in order.
+    ///
+    /// The node after each one is found before `f` is called, so `f` may move or remove the node
+    /// it is given without disrupting the walk.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn for_each_node<F: FnMut(ArcNode<T>)>(self: &Arc<Self>, f: F) {
+        self.iter().for_each(f);
+    }
+
+    /// Calls `f` with each node of the list, in order, along with the nodes before and after it,
+    /// which are `None` at the ends of the list.
+    ///
+    /// No node is locked while `f` runs, so `f` may lock any of the three.
+    // lock order:
+    //  self.head {}
+    //  each node, one at a time {}
+    pub fn each_node_with_neighbors<F>(self: &Arc<Self>, mut f: F)
+    where
+        F: FnMut(Option<&ArcNode<T>>, &ArcNode<T>, Option<&ArcNode<T>>),
+    {
+        let mut prev = None;
+        let mut node = self.head();
+        while let Some(this) = node {
+            let next = next_of(&this);
+            f(prev.as_ref(), &this, next.as_ref());
+            prev = Some(this);
+            node = next;
+        }
+    }
+
+    /// Calls `f` on the data of each node of the list, in order, returning the list so that further
+    /// operations can be chained.
+    ///
+    /// Each node is only locked for the duration of its call to `f`.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn map_in_place<F: FnMut(&mut T)>(self: &Arc<Self>, mut f: F) -> &Arc<Self> {
+        for node in self.iter() {
+            f(&mut lock(&node).data);
+        }
+        self
+    }
+
+    /// Calls `f` with the index and data of each node of the list, in order.
+    ///
+    /// Each node is only locked for the duration of its call to `f`.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn each_with_index_mut<F: FnMut(usize, &mut T)>(&self, mut f: F) {
+        for (i, node) in self.iter().enumerate() {
+            f(i, &mut lock(&node).data);
+        }
+    }
+
+    /// Clones the data of each node into a [`Vec`], in order.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().map(|node| lock(&node).data.clone()).collect()
+    }
+
+    /// Clones the data of each node into a [`Vec`], from tail to head.
+    // lock order:
+    //  self.tail {}
+    //  each node, one at a time {}
+    pub fn to_vec_rev(self: &Arc<Self>) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.tail()
+            .map(|tail| {
+                List::iter_rev_from(&tail)
+                    .map(|node| lock(&node).data.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Clones the data of each node into a boxed slice, in order.
+    ///
+    /// This is a more compact snapshot than [`to_vec`](Self::to_vec) when it is kept around for a
+    /// long time.
+    // lock order:
+    //  as in `to_vec`
+    pub fn to_boxed_slice(self: &Arc<Self>) -> Box<[T]>
+    where
+        T: Clone,
+    {
+        self.to_vec().into_boxed_slice()
+    }
+
+    /// Finds the node in this list whose [`node_id`](Node::node_id) is `id`.
+    ///
+    /// Ids are only unique among nodes which are alive at the same time, so an id stored after
+    /// its node has been freed may resolve to an unrelated node which reused the same memory.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn find_node_by_id(&self, id: usize) -> Option<ArcNode<T>> {
+        self.iter().find(|node| Node::node_id(node) == id)
+    }
+
+    /// Creates a new list containing clones of this list's data, sorted according to `cmp`,
+    /// returning it along with its nodes in order.
+    ///
+    /// This list is left unchanged. The sort is stable.
+    // lock order:
+    //  as in `to_vec`
+    pub fn sorted_copy_by<F: FnMut(&T, &T) -> CmpOrdering>(
+        self: &Arc<Self>,
+        cmp: F,
+    ) -> (Arc<List<T>>, Vec<ArcNode<T>>)
+    where
+        T: Clone,
+    {
+        let mut data = self.to_vec();
+        data.sort_by(cmp);
+        let sorted = List::new();
+        let nodes = data
+            .into_iter()
+            .map(|data| sorted.push_back(data))
+            .collect();
+        (sorted, nodes)
+    }
+
+    /// Creates a new list containing `f` applied to each element of this list, stopping at the
+    /// first element for which `f` returns `None`, and returns it along with its nodes in order.
+    ///
+    /// This list is left unchanged.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn map_while<U, F: FnMut(&T) -> Option<U>>(
+        self: &Arc<Self>,
+        mut f: F,
+    ) -> (Arc<List<U>>, Vec<ArcNode<U>>) {
+        let mapped = List::new();
+        let nodes = self
+            .iter()
+            .map_while(|node| f(&lock(&node).data))
+            .map(|data| mapped.push_back(data))
+            .collect();
+        (mapped, nodes)
+    }
+
+    /// Creates a new list of the values emitted by `f` as it threads `state` through each
+    /// element of this list, stopping at the first `None`, and returns it along with its nodes in
+    /// order.
+    ///
+    /// This list is left unchanged.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn scan<St, U, F: FnMut(&mut St, &T) -> Option<U>>(
+        self: &Arc<Self>,
+        init: St,
+        mut f: F,
+    ) -> (Arc<List<U>>, Vec<ArcNode<U>>) {
+        let mut state = init;
+        self.map_while(|data| f(&mut state, data))
+    }
+
+    /// Replaces the contents of the list with `nodes`, sorted by their data, and returns them in
+    /// their new order.
+    ///
+    /// Any nodes of this list which are not in `nodes` are removed, and each of `nodes` is removed
+    /// from its current list first. The sort is stable. The handles are handed back so that the
+    /// caller keeps the nodes alive.
+    // lock order:
+    //  for each comparison, as in `lock_pair`
+    //  then as in `drain`
+    //  then, for each node, as in `put_back`
+    pub fn rebuild_sorted(self: &Arc<Self>, mut nodes: Vec<ArcNode<T>>) -> Vec<ArcNode<T>>
+    where
+        T: Ord,
+    {
+        nodes.sort_by(|a, b| {
+            if Arc::ptr_eq(a, b) {
+                return CmpOrdering::Equal;
+            }
+            let (a, b) = self.lock_pair(a, b);
+            a.data.cmp(&b.data)
+        });
+        self.drain().for_each(drop);
+        for node in &nodes {
+            self.put_back(node);
+        }
+        nodes
+    }
+
+    /// Returns an additional strong handle to each node of the list, in order.
+    ///
+    /// Unlike [`into_node_vec`](Self::into_node_vec), the nodes stay in the list; this is for
+    /// keeping them alive independently of their existing owners.
+    // lock order:
+    //  as in `iter`
+    pub fn clone_handles(&self) -> Vec<ArcNode<T>> {
+        self.iter().collect()
+    }
+
+    /// Creates an iterator over each pair of adjacent nodes in the list, from head to tail.
+    ///
+    /// A list of `n` nodes yields `n - 1` pairs.
+    // lock order:
+    //  as in `iter`
+    pub fn pairs(self: &Arc<Self>) -> Pairs<T> {
+        let mut iter = self.iter();
+        Pairs {
+            prev: iter.next(),
+            iter,
+        }
+    }
+
+    /// Creates an iterator over groups of `n` adjacent nodes in the list, starting from the tail.
+    ///
+    /// The first group holds the last `n` nodes, the next group the `n` before them, and so on.
+    /// Each group is in list order, and the last group yielded holds the head and may be shorter
+    /// than `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    // lock order:
+    //  self.tail {}
+    //  each node, one at a time {}
+    pub fn rchunks(self: &Arc<Self>, n: usize) -> RChunks<T> {
+        assert!(n != 0, "chunk size must be non-zero");
+        RChunks {
+            iter: IterRev { next: self.tail() },
+            n,
+        }
+    }
+
+    /// Creates an iterator over `node` and the nodes after it.
+    ///
+    /// If `node` is not linked into a list, the iterator yields nothing.
+    ///
+    /// Unlike [`iter`](Self::iter), the number of nodes left isn't known without walking back to
+    /// the head, so this iterator is not an [`ExactSizeIterator`].
+    // lock order:
+    //  node {}
+    //  each node, one at a time {}
+    pub fn iter_from(node: &ArcNode<T>) -> IterFrom<T> {
+        let linked = lock(node).parent().is_some();
+        IterFrom {
+            next: linked.then(|| Arc::clone(node)),
+        }
+    }
+
+    /// Creates an iterator over `node` and the nodes before it, walking backward toward the head.
+    ///
+    /// If `node` is not linked into a list, the iterator yields nothing.
+    // lock order:
+    //  node {}
+    //  each node, one at a time {}
+    pub fn iter_rev_from(node: &ArcNode<T>) -> IterRev<T> {
+        let linked = lock(node).is_linked();
+        IterRev {
+            next: linked.then(|| Arc::clone(node)),
+        }
+    }
+
+    /// Counts the elements of the list for which `f` returns `true`.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn count_where<F: FnMut(&T) -> bool>(&self, mut f: F) -> usize {
+        self.iter().filter(|node| f(&lock(node).data)).count()
+    }
+
+    /// Calls `f` on each element of the list in order, stopping at the first error.
+    ///
+    /// Each node is only locked for the duration of its call to `f`.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn try_for_each<E, F: FnMut(&T) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        self.iter().try_for_each(|node| {
+            let result = f(&lock(&node).data);
+            result
+        })
+    }
+
+    /// Finds the last node of the list whose data satisfies `pred`, searching backward from the
+    /// tail.
+    // lock order:
+    //  self.tail {}
+    //  each node, one at a time {}
+    pub fn find_last<F: FnMut(&T) -> bool>(self: &Arc<Self>, mut pred: F) -> Option<ArcNode<T>> {
+        let mut node = self.tail();
+        while let Some(this) = node {
+            let lock = lock(&this);
+            if pred(&lock.data) {
+                drop(lock);
+                return Some(this);
+            }
+            node = lock.prev.upgrade();
+        }
+        None
+    }
+
+    /// Returns the node with the greatest data, or the first such node if there are several.
+    // lock order:
+    //  as in `best_by`
+    pub fn max(self: &Arc<Self>) -> Option<ArcNode<T>>
+    where
+        T: Ord,
+    {
+        self.best_by(|candidate, best| candidate > best)
+    }
+
+    /// Returns the node with the least data, or the first such node if there are several.
+    // lock order:
+    //  as in `best_by`
+    pub fn min(self: &Arc<Self>) -> Option<ArcNode<T>>
+    where
+        T: Ord,
+    {
+        self.best_by(|candidate, best| candidate < best)
+    }
+
+    /// Returns the first node such that no later node is `better` than it.
+    // lock order:
+    //  for each node, as in `lock_pair` with the best node so far first
+    fn best_by<F: FnMut(&T, &T) -> bool>(&self, mut better: F) -> Option<ArcNode<T>> {
+        let mut nodes = self.iter();
+        let mut best = nodes.next()?;
+        for node in nodes {
+            let (best_lock, node_lock) = self.lock_pair(&best, &node);
+            let is_better = better(&node_lock.data, &best_lock.data);
+            drop((best_lock, node_lock));
+            if is_better {
+                best = node;
+            }
+        }
+        Some(best)
+    }
+
+    /// Combines the elements of the list in order, starting from a clone of the head's data.
+    ///
+    /// Returns `None` if the list is empty.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn reduce<F: FnMut(T, &T) -> T>(&self, mut f: F) -> Option<T>
+    where
+        T: Clone,
+    {
+        let mut nodes = self.iter();
+        let first = nodes.next()?;
+        let init = lock(&first).data.clone();
+        Some(nodes.fold(init, |acc, node| f(acc, &lock(&node).data)))
+    }
+
+    /// Checks whether `f` holds for every pair of adjacent elements, stopping at the first pair
+    /// for which it doesn't.
+    // lock order:
+    //  for each pair of adjacent nodes, as in `lock_pair`
+    pub fn is_sorted_by<F: FnMut(&T, &T) -> bool>(&self, mut f: F) -> bool {
+        let mut nodes = self.iter();
+        let Some(mut prev) = nodes.next() else {
+            return true;
+        };
+        for node in nodes {
+            let (prev_lock, node_lock) = self.lock_pair(&prev, &node);
+            let sorted = f(&prev_lock.data, &node_lock.data);
+            drop((prev_lock, node_lock));
+            if !sorted {
+                return false;
+            }
+            prev = node;
+        }
+        true
+    }
+
+    /// Checks whether the elements of the list are in non-decreasing order.
+    // lock order:
+    //  as in `is_sorted_by`
+    pub fn is_sorted(&self) -> bool
+    where
+        T: Ord,
+    {
+        self.is_sorted_by(|a, b| a <= b)
+    }
+
+    /// Returns the index of the first element for which `pred` returns `false`, or the length of
+    /// the list if there is none.
+    ///
+    /// As with [`slice::partition_point`], the list is assumed to be partitioned according to
+    /// `pred`. Since a linked list can't be binary searched, this is a linear scan.
+    // lock order:
+    //  as in `count_while`
+    pub fn partition_point<F: FnMut(&T) -> bool>(self: &Arc<Self>, pred: F) -> usize {
+        self.count_while(pred)
+    }
+
+    /// Groups handles to the nodes of the list by the key `f` computes from their data.
+    ///
+    /// Nodes with equal keys are grouped together wherever they appear in the list, and each group
+    /// keeps the nodes in list order.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn group_by_key<K: Eq + Hash, F: FnMut(&T) -> K>(
+        &self,
+        mut f: F,
+    ) -> HashMap<K, Vec<ArcNode<T>>> {
+        let mut groups = HashMap::<_, Vec<_>>::new();
+        for node in self.iter() {
+            let key = f(&lock(&node).data);
+            groups.entry(key).or_default().push(node);
+        }
+        groups
+    }
+
+    /// Counts the nodes from the head up to the first one for which `pred` returns `false`.
+    ///
+    /// Unlike [`count_where`](Self::count_where), this stops at the first node which doesn't
+    /// match.
+    // lock order:
+    //  each node, one at a time {}
+    pub fn count_while<F: FnMut(&T) -> bool>(self: &Arc<Self>, mut pred: F) -> usize {
+        self.iter()
+            .take_while(|node| pred(&lock(node).data))
+            .count()
+    }
+
+    /// Counts the nodes of the list by following `next` pointers from the head and by following
+    /// `prev` pointers from the tail, returning both counts.
+    ///
+    /// In a well-formed list the two counts are equal, so a mismatch indicates corrupted links.
+    /// This does not terminate if either direction contains a cycle, which can be checked for
+    /// first with [`has_cycle`](Self::has_cycle).
+    // lock order:
+    //  self.head {}
+    //  self.tail {}
+    //  each node, one at a time {}
+    pub fn count_both_directions(self: &Arc<Self>) -> (usize, usize) {
+        let forward = self.iter().count();
+        let backward = IterRev { next: self.tail() }.count();
+        (forward, backward)
+    }
+
+    /// Checks whether following [`next`](Node::next) pointers from the head ever revisits a node.
+    ///
+    /// A well-formed list never contains a cycle, but one can be introduced by incorrect manual
+    /// relinking. This uses Floyd's tortoise-and-hare algorithm, so it runs in linear time and
+    /// constant space.
+    ///
+    /// The wraparound from the tail to the head of a [circular](Self::new_circular) list is not a
+    /// cycle in this sense, since the tail's own `next` link is still empty.
+    // lock order:
+    //  self.head {}
+    //  each node, one at a time {}
+    pub fn has_cycle(self: &Arc<Self>) -> bool {
+        let mut slow = self.head();
+        let mut fast = self.head();
+        loop {
+            fast = fast.as_ref().and_then(next_of).as_ref().and_then(next_of);
+            slow = slow.as_ref().and_then(next_of);
+            match (&slow, &fast) {
+                (_, None) => return false,
+                (Some(slow), Some(fast)) if Arc::ptr_eq(slow, fast) => return true,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// An iterator over the nodes of a [`List`], created by [`List::iter`].
+///
+/// The iterator's [`len`](ExactSizeIterator::len) is taken from the list's length when it is
+/// created, and counts down as nodes are yielded. If the list is modified concurrently, it may no
+/// longer match the number of nodes actually yielded, so only rely on it with exclusive access.
+pub struct Iter<T> {
+    next: Option<ArcNode<T>>,
+    /// The number of nodes expected to be yielded.
+    remaining: usize,
+}
+
+impl<T> Iterator for Iter<T> {
+    type Item = ArcNode<T>;
+
+    fn next(&mut self) -> Option<ArcNode<T>> {
+        let node = self.next.take()?;
+        self.next = next_of(&node);
+        self.remaining = self.remaining.saturating_sub(1);
+        Some(node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<T> {}
+
+/// An iterator over pairs of adjacent nodes of a [`List`], created by [`List::pairs`].
+pub struct Pairs<T> {
+    prev: Option<ArcNode<T>>,
+    iter: Iter<T>,
+}
+
+impl<T> Iterator for Pairs<T> {
+    type Item = (ArcNode<T>, ArcNode<T>);
+
+    fn next(&mut self) -> Option<(ArcNode<T>, ArcNode<T>)> {
+        let next = self.iter.next()?;
+        let prev = self.prev.replace(Arc::clone(&next))?;
+        Some((prev, next))
+    }
+}
+
+/// An iterator over groups of adjacent nodes of a [`List`] from back to front, created by
+/// [`List::rchunks`].
+pub struct RChunks<T> {
+    iter: IterRev<T>,
+    n: usize,
+}
+
+impl<T> Iterator for RChunks<T> {
+    type Item = Vec<ArcNode<T>>;
+
+    fn next(&mut self) -> Option<Vec<ArcNode<T>>> {
+        let mut chunk: Vec<_> = self.iter.by_ref().take(self.n).collect();
+        if chunk.is_empty() {
+            return None;
+        }
+        chunk.reverse();
+        Some(chunk)
+    }
+}
+
+/// An iterator over a node of a [`List`] and the nodes after it, created by [`List::iter_from`].
+pub struct IterFrom<T> {
+    next: Option<ArcNode<T>>,
+}
+
+impl<T> Iterator for IterFrom<T> {
+    type Item = ArcNode<T>;
+
+    fn next(&mut self) -> Option<ArcNode<T>> {
+        let node = self.next.take()?;
+        self.next = next_of(&node);
+        Some(node)
+    }
+}
+
+/// An iterator over the nodes of a [`List`] from back to front, created by
+/// [`List::iter_rev_from`].
+pub struct IterRev<T> {
+    next: Option<ArcNode<T>>,
+}
+
+impl<T> Iterator for IterRev<T> {
+    type Item = ArcNode<T>;
+
+    fn next(&mut self) -> Option<ArcNode<T>> {
+        let node = self.next.take()?;
+        self.next = prev_of(&node);
+        Some(node)
+    }
+}
+
+/// An iterator which removes nodes from the front of a [`List`], created by [`List::drain`].
+pub struct Drain<T> {
+    list: Arc<List<T>>,
+}
+
+impl<T> Iterator for Drain<T> {
+    type Item = ArcNode<T>;
+
+    fn next(&mut self) -> Option<ArcNode<T>> {
+        self.list.pop_front()
+    }
+}
+
+impl<T> Drop for Drain<T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// An iterator which removes nodes from the back of a [`List`], created by
+/// [`List::drain_rev`].
+pub struct DrainRev<T> {
+    list: Arc<List<T>>,
+}
+
+impl<T> Iterator for DrainRev<T> {
+    type Item = ArcNode<T>;
+
+    fn next(&mut self) -> Option<ArcNode<T>> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> Drop for DrainRev<T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// An iterator which drains a [`List`], yielding owned data, created by
+/// [`List::into_iter_data`].
+pub struct IntoIterData<T> {
+    list: Arc<List<T>>,
+    /// The handles taken from the list, keyed by [`Node::node_id`].
+    owned: HashMap<usize, ArcNode<T>>,
+}
+
+impl<T> Iterator for IntoIterData<T> {
+    type Item = Result<T, ArcNode<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.pop_front()?;
+        self.owned.remove(&Node::node_id(&node));
+        Some(Node::try_into_data(node))
+    }
+}
+
+pub type ArcNode<T> = Arc<Mutex<Node<T>>>;
+type WeakNode<T> = Weak<Mutex<Node<T>>>;
+
+/// Locks `node` just long enough to retrieve the node after it.
+fn next_of<T>(node: &ArcNode<T>) -> Option<ArcNode<T>> {
+    let next = lock(node).next.upgrade();
+    next
+}
+
+/// Locks `node` just long enough to retrieve the node before it.
+fn prev_of<T>(node: &ArcNode<T>) -> Option<ArcNode<T>> {
+    let prev = lock(node).prev.upgrade();
+    prev
+}
+
+/// Takes a [`Weak<T>`] and [`upgrade`](Weak::upgrade)s it, leaving [`Weak::new()`] in it's place.
+fn take_weak<T>(ptr: &mut Weak<T>) -> Option<Arc<T>> {
+    std::mem::take(ptr).upgrade()
+}
+
+pub struct Node<T> {
+    pub data: T,
+    parent: Weak<List<T>>,
+    prev: WeakNode<T>,
+    next: WeakNode<T>,
+}
+
+impl<T> Node<T> {
+    pub fn new(data: T) -> ArcNode<T> {
+        Arc::new(Mutex::new(Node {
+            data,
+            parent: Weak::new(),
+            prev: Weak::new(),
+            next: Weak::new(),
+        }))
+    }
+
+    /// Locks `node`, panicking if the lock is poisoned.
+    ///
+    /// This is equivalent to `node.lock().unwrap()`; use [`lock_with`](Self::lock_with) to
+    /// recover from a poisoned lock instead.
+    // lock order:
+    //  node {}
+    pub fn lock(node: &ArcNode<T>) -> NodeGuard<'_, T> {
+        Self::lock_with(node, PoisonPolicy::Panic)
+    }
+
+    /// Locks `node`, applying `policy` if the lock is poisoned.
+    // lock order:
+    //  node {}
+    pub fn lock_with(node: &ArcNode<T>, policy: PoisonPolicy) -> NodeGuard<'_, T> {
+        NodeGuard(node.lock().unwrap_or_else(|err| policy.apply(err)))
+    }
+
+    // lock order:
+    //  self (implicit) ({
+    //    self.parent.tail {
+    //      self.parent.head {}
+    //    }
+    //  }|{
+    //    self.parent.head {
+    //      self.next {}
+    //    }
+    //  }|{
+    //    self.parent.tail {
+    //      self.prev {}
+    //    }
+    //  }|{
+    //    self.prev {
+    //      self.next {}
+    //    }
+    //  })
+    /// Removes the node from its parent [`List`].
+    ///
+    /// Subscribers to the [length](List::subscribe_len) of the list are notified before this
+    /// returns, while the node is still locked.
+    pub fn remove(&mut self) {
+        if let Some(parent) = self.remove_unnotified() {
+            parent.notify_len();
+        }
+    }
+
+    /// Removes the node from its parent [`List`] without notifying subscribers to its length,
+    /// returning the parent.
+    ///
+    /// The caller must call [`List::notify_len`] on the parent once every lock is released.
+    // lock order:
+    //  as in `remove`
+    fn remove_unnotified(&mut self) -> Option<Arc<List<T>>> {
+        let parent = self.unlink()?;
+        parent.len.fetch_sub(1, Ordering::Relaxed);
+        Some(parent)
+    }
+
+    /// Unlinks the node from its parent [`List`], returning the parent, whose length still counts
+    /// the node.
+    ///
+    /// This is for moving the node to a new place, which accounts for the length with
+    /// [`List::count_linked`].
+    // lock order:
+    //  as in `remove`
+    fn unlink(&mut self) -> Option<Arc<List<T>>> {
+        let parent = if let Some(parent) = take_weak(&mut self.parent) {
+            parent
+        } else {
+            // already not in a list
+            debug_assert!(self.prev.upgrade().is_none());
+            debug_assert!(self.next.upgrade().is_none());
+            return None;
+        };
+        match (take_weak(&mut self.prev), take_weak(&mut self.next)) {
+            (None, None) => {
+                // only element of list
+                let mut tail = lock(&parent.tail);
+                parent.set_head(&mut lock(&parent.head), Weak::new());
+                parent.set_tail(&mut tail, Weak::new());
+            }
+            (None, Some(next)) => {
+                // head of list
+                let mut head = lock(&parent.head);
+                let mut next_lock = lock(&next);
+                parent.set_head(&mut head, Arc::downgrade(&next));
+                next_lock.prev = Weak::new();
+            }
+            (Some(prev), None) => {
+                // tail of list
+                let mut tail = lock(&parent.tail);
+                let mut prev_lock = lock(&prev);
+                parent.set_tail(&mut tail, Arc::downgrade(&prev));
+                prev_lock.next = Weak::new();
+            }
+            (Some(prev), Some(next)) => {
+                // middle of list, don't need to lock `parent`
+                let mut prev_lock = lock(&prev);
+                let mut next_lock = lock(&next);
+                prev_lock.next = Arc::downgrade(&next);
+                next_lock.prev = Arc::downgrade(&prev);
+            }
+        }
+        Some(parent)
+    }
+
+    /// Locks two distinct nodes without risking deadlock, returning their guards in the order the
+    /// nodes were given.
+    ///
+    /// The nodes are always locked in order of address, so two threads locking the same pair of
+    /// nodes can't deadlock, whichever order they pass them in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are the same node.
+    // lock order:
+    //  the node with the lower address {
+    //    the node with the higher address {}
+    //  }
+    pub fn lock_two<'a>(
+        a: &'a ArcNode<T>,
+        b: &'a ArcNode<T>,
+    ) -> (MutexGuard<'a, Node<T>>, MutexGuard<'a, Node<T>>) {
+        assert!(!Arc::ptr_eq(a, b), "cannot lock a node twice");
+        if Arc::as_ptr(a) < Arc::as_ptr(b) {
+            let a_lock = lock(a);
+            (a_lock, lock(b))
+        } else {
+            let b_lock = lock(b);
+            (lock(a), b_lock)
+        }
+    }
+
+    /// Replaces the data of `node` with the result of calling `f` on it, holding the node's lock
+    /// throughout.
+    ///
+    /// Moving the data into `f` leaves the node without a value until `f` returns, and if `f`
+    /// panics there is no new value to put back. Rather than abort the process in that case, or
+    /// read the data out with `unsafe`, this requires `T: Default`: while `f` runs the node holds
+    /// `T::default()`, and if `f` panics that default is left in place. For types without a
+    /// default, mutate the data in place through [`Node::lock`] instead.
+    // lock order:
+    //  node {}
+    pub fn update<F: FnOnce(T) -> T>(node: &ArcNode<T>, f: F)
+    where
+        T: Default,
+    {
+        let mut lock = lock(node);
+        let data = std::mem::take(&mut lock.data);
+        lock.data = f(data);
+    }
+
+    /// Takes the data out of `node`, if this is the only strong reference to it.
+    ///
+    /// Otherwise, the handle is returned unchanged. This does not remove the node from its list
+    /// first, so it should already have been removed; taking the data out of a linked node leaves
+    /// its neighbors with dangling links.
+    pub fn try_into_data(node: ArcNode<T>) -> Result<T, ArcNode<T>> {
+        let node = Arc::try_unwrap(node)?;
+        Ok(node.into_inner().unwrap_or_else(poisoned).data)
+    }
+
+    /// Returns the position of `node` in its list, or `None` if it is not in a list.
+    ///
+    /// This walks backward from `node` to the head, so it takes time proportional to the index.
+    // lock order:
+    //  as in `List::iter_rev_from`
+    pub fn index(node: &ArcNode<T>) -> Option<usize> {
+        List::iter_rev_from(node).count().checked_sub(1)
+    }
+
+    /// Returns an id for `node`, derived from its address.
+    ///
+    /// Two handles have the same id if and only if they refer to the same node, as long as that
+    /// node is alive. Once a node is freed, its id may be reused by a new node.
+    pub fn node_id(node: &ArcNode<T>) -> usize {
+        Arc::as_ptr(node) as usize
+    }
+
+    /// Returns the address of `node`, for use as an identity.
+    ///
+    /// The pointer is only meaningful while some strong or weak reference to the node exists;
+    /// after that, a new node may be allocated at the same address. Wrap it in a [`NodeKey`] to
+    /// use it as a map key.
+    pub fn data_ptr(node: &ArcNode<T>) -> *const Mutex<Node<T>> {
+        Arc::as_ptr(node)
+    }
+
+    /// Checks whether `a` and `b` are handles to the same node.
+    pub fn same_node(a: &ArcNode<T>, b: &ArcNode<T>) -> bool {
+        Arc::ptr_eq(a, b)
+    }
+
+    /// Returns the address of the list `node` belongs to, without upgrading the parent pointer.
+    ///
+    /// This is meant for grouping handles by list: nodes of the same list return the same
+    /// pointer. It must not be dereferenced, and is only meaningful while the list is alive;
+    /// after that, a new list may be allocated at the same address. A node which is not in a list
+    /// returns a sentinel which does not point to any list.
+    // lock order:
+    //  node {}
+    pub fn parent_ptr(node: &ArcNode<T>) -> *const List<T> {
+        lock(node).parent.as_ptr()
+    }
+
+    /// Returns the number of weak references to `node`.
+    ///
+    /// While `node` is linked, this includes the `prev`/`next` pointers of its neighbors and the
+    /// list's `head`/`tail` pointers. The head and tail pointers are each counted twice, since the
+    /// list also caches a copy of both, so in a list of three nodes the counts are 3, 2 and 3. A
+    /// node which was recently the head or tail may also have a temporary extra count, from a
+    /// replaced copy the cache is keeping until a concurrent read has finished with it. This is a
+    /// useful diagnostic for checking that a removed node is no longer referenced by the list.
+    pub fn weak_count_of(node: &ArcNode<T>) -> usize {
+        Arc::weak_count(node)
+    }
+
+    /// Checks whether this node is currently linked into a [`List`].
+    // lock order:
+    //  self (implicit) {}
+    pub fn is_linked(&self) -> bool {
+        self.parent.strong_count() > 0
+    }
+
+    /// Retrieves the [`List`] this node belongs to.
+    // lock order:
+    //  self (implicit) {}
+    pub fn parent(&self) -> Option<Arc<List<T>>> {
+        self.parent.upgrade()
+    }
+
+    /// Overwrites this node's `prev` pointer, bypassing the list's invariants.
+    ///
+    /// This is only for constructing corrupted lists in tests.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn set_prev(&mut self, prev: Option<&ArcNode<T>>) {
+        self.prev = prev.map_or_else(Weak::new, Arc::downgrade);
+    }
+
+    /// Overwrites this node's `next` pointer, bypassing the list's invariants.
+    ///
+    /// This is only for constructing corrupted lists in tests.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn set_next(&mut self, next: Option<&ArcNode<T>>) {
+        self.next = next.map_or_else(Weak::new, Arc::downgrade);
+    }
+
+    /// Overwrites this node's `parent` pointer, bypassing the list's invariants.
+    ///
+    /// This is only for constructing corrupted lists in tests.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn set_parent(&mut self, parent: Option<&Arc<List<T>>>) {
+        self.parent = parent.map_or_else(Weak::new, Arc::downgrade);
+    }
+
+    /// Checks whether this node belongs to `list`.
+    ///
+    /// This is the node-side counterpart of [`List::contains_node`], for when the node is already
+    /// locked.
+    pub fn in_list(&self, list: &Arc<List<T>>) -> bool {
+        self.parent
+            .upgrade()
+            .is_some_and(|parent| Arc::ptr_eq(&parent, list))
+    }
+
+    /// Checks whether this node's parent is `list`.
+    fn parent_is(&self, list: &List<T>) -> bool {
+        std::ptr::eq(self.parent.as_ptr(), list)
+    }
+
+    /// Retrieves the node after this one.
+    ///
+    /// If this is the tail of a [circular](List::new_circular) list, this returns the head, which
+    /// is this node itself if it is the only one in the list. The head is read from the list's
+    /// lock-free cached copy, so this never locks the list while the node is locked.
+    // lock order:
+    //  self (implicit) {}
+    pub fn next(&self) -> Option<ArcNode<T>> {
+        self.next
+            .upgrade()
+            .or_else(|| self.circular_parent()?.head_cache.load())
+    }
+
+    /// Retrieves the node before this one.
+    ///
+    /// If this is the head of a [circular](List::new_circular) list, this returns the tail, which
+    /// is this node itself if it is the only one in the list. As with [`next`](Self::next), the
+    /// tail is read from a lock-free cached copy.
+    // lock order:
+    //  self (implicit) {}
+    pub fn prev(&self) -> Option<ArcNode<T>> {
+        self.prev
+            .upgrade()
+            .or_else(|| self.circular_parent()?.tail_cache.load())
+    }
+
+    /// Returns the parent list, if it is circular.
+    fn circular_parent(&self) -> Option<Arc<List<T>>> {
+        self.parent.upgrade().filter(|parent| parent.circular)
+    }
+}
+
+/// A key identifying a node by its address, for side tables which shouldn't keep nodes alive.
+///
+/// Two keys are equal if and only if they were created from handles to the same node. As with
+/// [`Node::data_ptr`], keys of a freed node may collide with those of a node allocated later, so
+/// entries should be removed when their node is dropped.
+pub struct NodeKey<T>(*const Mutex<Node<T>>);
+
+impl<T> NodeKey<T> {
+    /// Creates the key of `node`.
+    pub fn new(node: &ArcNode<T>) -> Self {
+        Self(Node::data_ptr(node))
+    }
+
+    /// Returns the address of the node this key was created from.
+    pub fn ptr(&self) -> *const Mutex<Node<T>> {
+        self.0
+    }
+}
+
+impl<T> Clone for NodeKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeKey<T> {}
+
+impl<T> PartialEq for NodeKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl<T> Eq for NodeKey<T> {}
+
+impl<T> Hash for NodeKey<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for NodeKey<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NodeKey").field(&self.0).finish()
+    }
+}
+
+/// A lock on a [`Node`], created by [`Node::lock`].
+pub struct NodeGuard<'a, T>(MutexGuard<'a, Node<T>>);
+
+impl<T> Deref for NodeGuard<'_, T> {
+    type Target = Node<T>;
+
+    fn deref(&self) -> &Node<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NodeGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Node<T> {
+        &mut self.0
+    }
+}
+
+/// Extension methods for [`ArcNode`].
+pub trait ArcNodeExt<T> {
+    /// Locks the node, returning a guard which dereferences directly to its data.
+    ///
+    /// The node stays locked for as long as the guard is held, so holding it while performing
+    /// other operations on the list risks deadlock.
+    fn data_guard(&self) -> DataGuard<'_, T>;
+
+    /// Removes the node from its list, then takes its data out if this was the only strong
+    /// reference to it.
+    ///
+    /// If the node is still referenced elsewhere, it stays removed but this returns `None`.
+    fn detach(self) -> Option<T>;
+}
+
+impl<T> ArcNodeExt<T> for ArcNode<T> {
+    // lock order:
+    //  self {}
+    fn data_guard(&self) -> DataGuard<'_, T> {
+        DataGuard(Node::lock(self))
+    }
+
+    // lock order:
+    //  as in `Node::remove`
+    fn detach(self) -> Option<T> {
+        let parent = lock(&self).remove_unnotified();
+        if let Some(parent) = parent {
+            parent.notify_len();
+        }
+        Node::try_into_data(self).ok()
+    }
+}
+
+/// A lock on a [`Node`] which dereferences to its data, created by
+/// [`ArcNodeExt::data_guard`].
+pub struct DataGuard<'a, T>(NodeGuard<'a, T>);
+
+impl<T> Deref for DataGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0.data
+    }
+}
+
+impl<T> DerefMut for DataGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{
+        ArcNode, ArcNodeExt, Deque, Issue, List, Node, NodeError, NodeKey, PoisonPolicy, Queue,
+    };
+
+    /// A global allocator which counts the allocations made by each thread.
+    struct CountingAlloc;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    std::thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            // `try_with` fails while the thread is being torn down, when nothing is measured
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            // SAFETY: forwarded from the caller
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            // SAFETY: forwarded from the caller
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+    }
+
+    /// Returns the number of allocations made by the current thread so far.
+    fn allocations() -> usize {
+        ALLOCATIONS.with(|count| count.get())
+    }
+
+    /// Collects the data of the list from head to tail, checking that the `prev` links and the
+    /// tail agree with the `next` links along the way.
+    fn contents<T: Clone>(list: &Arc<List<T>>) -> Vec<T> {
+        let mut values = Vec::new();
+        let mut prev: Option<ArcNode<T>> = None;
+        let mut node = list.head();
+        while let Some(this) = node {
+            let lock = this.lock().unwrap();
+            assert_eq!(
+                lock.prev.upgrade().map(|prev| Arc::as_ptr(&prev)),
+                prev.as_ref().map(Arc::as_ptr)
+            );
+            values.push(lock.data.clone());
+            node = lock.next.upgrade();
+            drop(lock);
+            prev = Some(this);
+        }
+        assert_eq!(
+            list.tail().map(|tail| Arc::as_ptr(&tail)),
+            prev.as_ref().map(Arc::as_ptr)
+        );
+        assert_eq!(list.len(), values.len());
+        values
+    }
+
+    #[test]
     fn basic_functionality() {
         let values = ["a", "b", "c", "d"];
         let mut nodes = Vec::with_capacity(values.len());
         let list = List::new();
-        for v in values {
-            let node = list.push_back(v);
-            assert_eq!(node.lock().unwrap().data, v);
-            nodes.push(node);
+        for v in values {
+            let node = list.push_back(v);
+            assert_eq!(node.lock().unwrap().data, v);
+            nodes.push(node);
+        }
+
+        for node in nodes.iter().rev() {
+            list.put_back(node);
+        }
+
+        let mut node = list.head();
+        for v in values.iter().rev() {
+            let temp = node.unwrap();
+            let this = temp.lock().unwrap();
+            assert_eq!(&this.data, v);
+            node = this.next();
+        }
+        assert!(node.is_none());
+    }
+
+    #[test]
+    fn has_cycle() {
+        let list = List::new();
+        assert!(!list.has_cycle());
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        assert!(!list.has_cycle());
+
+        nodes[2].lock().unwrap().set_next(Some(&nodes[0]));
+        assert!(list.has_cycle());
+    }
+
+    #[test]
+    fn reverse_between() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        list.reverse_between(&nodes[1], &nodes[2]);
+        assert_eq!(contents(&list), ["a", "c", "b", "d"]);
+
+        list.reverse_between(&nodes[0], &nodes[3]);
+        assert_eq!(contents(&list), ["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn into_node_vec() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let drained = list.into_node_vec();
+        assert!(list.head().is_none());
+        assert!(list.tail().is_none());
+        let values: Vec<_> = drained
+            .iter()
+            .map(|node| {
+                let lock = node.lock().unwrap();
+                assert!(!lock.is_linked());
+                assert!(lock.prev().is_none() && lock.next().is_none());
+                lock.data
+            })
+            .collect();
+        assert_eq!(values, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn into_iter_data_owned() {
+        let list = List::with_nodes(vec![String::from("a"), String::from("b")]);
+        let mut values = Vec::new();
+        for item in Arc::clone(&list).into_iter_data() {
+            values.push(item.ok().unwrap());
+        }
+        assert_eq!(values, ["a", "b"]);
+        assert!(list.is_empty() && list.head().is_none());
+    }
+
+    #[test]
+    fn count_where() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5, 6].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.count_where(|x| x % 2 == 0), 3);
+    }
+
+    #[test]
+    fn retain_first_n() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        let removed = list.retain_first_n(2);
+        assert_eq!(contents(&list), [1, 2]);
+        let removed: Vec<_> = removed
+            .iter()
+            .map(|node| node.lock().unwrap().data)
+            .collect();
+        assert_eq!(removed, [3, 4, 5]);
+
+        assert!(list.retain_first_n(2).is_empty());
+        assert_eq!(contents(&list), [1, 2]);
+    }
+
+    #[test]
+    fn append_prepend_slice() {
+        let list = List::new();
+        let _a = list.push_back("a");
+
+        let _xy = list.append_slice(&["x", "y"]);
+        assert_eq!(contents(&list), ["a", "x", "y"]);
+
+        let _uv = list.prepend_slice(&["u", "v"]);
+        assert_eq!(contents(&list), ["u", "v", "a", "x", "y"]);
+
+        let empty = List::new();
+        let _uv = empty.prepend_slice(&["u", "v"]);
+        assert_eq!(contents(&empty), ["u", "v"]);
+    }
+
+    #[test]
+    fn try_for_each() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 0, 3].map(|v| list.push_back(v)).into();
+
+        let mut seen = Vec::new();
+        let result = list.try_for_each(|&x| {
+            if x == 0 {
+                return Err("zero");
+            }
+            seen.push(x);
+            Ok(())
+        });
+        assert_eq!(result, Err("zero"));
+        assert_eq!(seen, [1, 2]);
+
+        assert_eq!(list.try_for_each(|_| Ok::<_, ()>(())), Ok(()));
+    }
+
+    #[test]
+    fn find_last() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3, 4, 5, 6].map(|v| list.push_back(v)).into();
+
+        let found = list.find_last(|x| x % 2 == 0).unwrap();
+        assert!(Arc::ptr_eq(&found, &nodes[5]));
+        let found = list.find_last(|x| *x < 3).unwrap();
+        assert!(Arc::ptr_eq(&found, &nodes[1]));
+        assert!(list.find_last(|x| *x > 6).is_none());
+    }
+
+    #[test]
+    fn swap_head_tail() {
+        let list = List::new();
+        let _a = list.push_back("a");
+        list.swap_head_tail();
+        assert_eq!(contents(&list), ["a"]);
+
+        let _b = list.push_back("b");
+        list.swap_head_tail();
+        assert_eq!(contents(&list), ["b", "a"]);
+
+        let _cd = list.append_slice(&["c", "d"]);
+        list.swap_head_tail();
+        assert_eq!(contents(&list), ["d", "a", "c", "b"]);
+    }
+
+    #[test]
+    fn put_front() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        list.put_front(&nodes[2]);
+        assert_eq!(contents(&list), ["c", "a", "b"]);
+        list.put_front(&nodes[2]);
+        assert_eq!(contents(&list), ["c", "a", "b"]);
+
+        let other = List::new();
+        other.put_front(&nodes[1]);
+        let _d = other.push_front("d");
+        assert_eq!(contents(&list), ["c", "a"]);
+        assert_eq!(contents(&other), ["d", "b"]);
+    }
+
+    #[test]
+    fn swap_with_neighbors() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        assert!(list.swap_with_next(&nodes[1]));
+        assert_eq!(contents(&list), ["a", "c", "b"]);
+        assert!(!list.swap_with_next(&nodes[1]));
+
+        assert!(list.swap_with_prev(&nodes[2]));
+        assert_eq!(contents(&list), ["c", "a", "b"]);
+        assert!(!list.swap_with_prev(&nodes[2]));
+    }
+
+    #[test]
+    fn bubble_with_swap_with_next() {
+        let list = List::new();
+        let nodes: Vec<_> = [9, 1, 2, 3].map(|v| list.push_back(v)).into();
+
+        let mut swaps = 0;
+        while list.swap_with_next(&nodes[0]) {
+            swaps += 1;
+        }
+        assert_eq!(swaps, 3);
+        assert_eq!(contents(&list), [1, 2, 3, 9]);
+    }
+
+    #[test]
+    fn same_list() {
+        let a = List::<()>::new();
+        let b = List::new();
+        assert!(!List::same_list(&a, &b));
+        assert!(List::same_list(&a, &Arc::clone(&a)));
+
+        let node = a.push_back(());
+        assert!(a.contains_node(&node));
+        assert!(!b.contains_node(&node));
+        assert!(List::same_list(&node.lock().unwrap().parent().unwrap(), &a));
+    }
+
+    #[test]
+    fn node_guard() {
+        let list = List::new();
+        let node = list.push_back(1);
+
+        let mut guard = Node::lock(&node);
+        assert_eq!(guard.data, 1);
+        guard.data += 1;
+        assert!(guard.next().is_none());
+        drop(guard);
+        assert_eq!(contents(&list), [2]);
+    }
+
+    #[test]
+    fn poison_policy() {
+        let list = List::new();
+        let node = list.push_back(1);
+        let poisoner = Arc::clone(&node);
+        let result = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poisoning the node");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(node.is_poisoned());
+
+        Node::lock_with(&node, PoisonPolicy::Recover).data += 1;
+        assert_eq!(Node::lock_with(&node, PoisonPolicy::Recover).data, 2);
+        let result = std::panic::catch_unwind(|| Node::lock(&node).data);
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(|| list.to_vec());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn drain_from() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        let drained = list.drain_from(&nodes[2]);
+        assert_eq!(contents(&list), [1, 2]);
+        let drained: Vec<_> = drained
+            .iter()
+            .map(|node| {
+                assert!(!node.lock().unwrap().is_linked());
+                node.lock().unwrap().data
+            })
+            .collect();
+        assert_eq!(drained, [3, 4, 5]);
+
+        list.drain_from(&nodes[0]);
+        assert_eq!(contents(&list), []);
+    }
+
+    #[test]
+    fn max_min_reduce() {
+        let list = List::new();
+        let nodes: Vec<_> = [3, 1, 4, 1, 5].map(|v| list.push_back(v)).into();
+
+        assert!(Arc::ptr_eq(&list.max().unwrap(), &nodes[4]));
+        assert!(Arc::ptr_eq(&list.min().unwrap(), &nodes[1]));
+        assert_eq!(list.reduce(|acc, x| acc + x), Some(14));
+
+        let empty = List::<i32>::new();
+        assert!(empty.max().is_none());
+        assert_eq!(empty.reduce(|acc, x| acc + x), None);
+    }
+
+    #[test]
+    fn dedup_global() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 1, 3, 2, 4].map(|v| list.push_back(v)).into();
+
+        list.dedup_global();
+        assert_eq!(contents(&list), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn deduplicate() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 1, 3, 2, 4].map(|v| list.push_back(v)).into();
+
+        list.deduplicate();
+        assert_eq!(contents(&list), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn head_tail_data() {
+        let list = List::new();
+        assert_eq!(list.head_data(), None);
+        assert_eq!(list.tail_data(), None);
+
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        assert_eq!(list.head_data(), Some("a"));
+        assert_eq!(list.tail_data(), Some("c"));
+    }
+
+    #[test]
+    fn rank() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        let other = List::new();
+        let foreign = other.push_back("x");
+
+        assert_eq!(list.rank(&nodes[0]), Some(0));
+        assert_eq!(list.rank(&nodes[2]), Some(2));
+        assert_eq!(list.rank(&foreign), None);
+        assert_eq!(Node::index(&foreign), Some(0));
+        foreign.lock().unwrap().remove();
+        assert_eq!(Node::index(&foreign), None);
+    }
+
+    #[test]
+    fn rotate_until() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        let head = list.rotate_until(|x| x % 2 == 0).unwrap();
+        assert!(Arc::ptr_eq(&head, &nodes[2]));
+        assert_eq!(contents(&list), [4, 5, 1, 3]);
+
+        assert!(list.rotate_until(|&x| x > 10).is_none());
+        assert_eq!(contents(&list), [4, 5, 1, 3]);
+    }
+
+    #[test]
+    fn cas_head() {
+        let list = List::new();
+        let a = Node::new("a");
+        assert!(list.cas_head(None, &a));
+
+        let stale = list.head();
+        let b = Node::new("b");
+        assert!(list.cas_head(stale.as_ref(), &b));
+        // `stale` is no longer the head
+        let c = Node::new("c");
+        assert!(!list.cas_head(stale.as_ref(), &c));
+        assert!(!list.cas_head(None, &c));
+        assert!(!c.lock().unwrap().is_linked());
+        assert_eq!(contents(&list), ["b", "a"]);
+    }
+
+    #[test]
+    fn rchunks() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c", "d", "e"].map(|v| list.push_back(v)).into();
+        let chunks: Vec<Vec<_>> = list
+            .rchunks(2)
+            .map(|chunk| chunk.iter().map(|node| node.lock().unwrap().data).collect())
+            .collect();
+        assert_eq!(chunks, [vec!["d", "e"], vec!["b", "c"], vec!["a"]]);
+        assert!(List::<i32>::new().rchunks(3).next().is_none());
+    }
+
+    #[test]
+    fn in_list() {
+        let list = List::new();
+        let other = List::new();
+        let a = list.push_back("a");
+        let _b = other.push_back("b");
+        assert!(a.lock().unwrap().in_list(&list));
+        assert!(!a.lock().unwrap().in_list(&other));
+        a.lock().unwrap().remove();
+        assert!(!a.lock().unwrap().in_list(&list));
+    }
+
+    #[test]
+    fn to_vec_rev() {
+        let list = List::new();
+        assert!(list.to_vec_rev().is_empty());
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        assert_eq!(list.to_vec_rev(), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn ends() {
+        let list = List::new();
+        assert!(matches!(list.ends(), (None, None)));
+        let a = list.push_back("a");
+        let (Some(head), Some(tail)) = list.ends() else {
+            panic!("list is not empty");
+        };
+        assert!(Node::same_node(&head, &tail));
+        assert!(Node::same_node(&head, &a));
+        let b = list.push_back("b");
+        let (Some(head), Some(tail)) = list.ends() else {
+            panic!("list is not empty");
+        };
+        assert!(Node::same_node(&head, &a));
+        assert!(Node::same_node(&tail, &b));
+    }
+
+    #[test]
+    fn link_front_back() {
+        let list = List::new();
+        let a = Node::new("a");
+        assert!(list.link_back(&a).is_none());
+        let b = Node::new("b");
+        let prev = list.link_back(&b).unwrap();
+        assert!(Arc::ptr_eq(&prev, &a));
+        let c = Node::new("c");
+        let prev = list.link_front(&c).unwrap();
+        assert!(Arc::ptr_eq(&prev, &a));
+        assert_eq!(contents(&list), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn peek_nth() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.peek_nth(2, |v| v.to_uppercase()), Some("C".to_owned()));
+        assert_eq!(list.peek_nth(3, |v| v.to_uppercase()), None);
+    }
+
+    #[test]
+    fn is_sorted() {
+        let sorted = List::new();
+        let _nodes: Vec<_> = [1, 2, 3].map(|v| sorted.push_back(v)).into();
+        assert!(sorted.is_sorted());
+        assert!(!sorted.is_sorted_by(|a, b| a > b));
+
+        let unsorted = List::new();
+        let _nodes: Vec<_> = [1, 3, 2].map(|v| unsorted.push_back(v)).into();
+        assert!(!unsorted.is_sorted());
+
+        assert!(List::<i32>::new().is_sorted());
+    }
+
+    #[test]
+    fn partition_point() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.partition_point(|x| *x < 3), 2);
+        assert_eq!(list.partition_point(|x| *x < 10), 5);
+        assert_eq!(list.partition_point(|_| false), 0);
+    }
+
+    #[test]
+    fn len() {
+        let list = List::new();
+        assert!(list.is_empty());
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        assert_eq!(list.len(), 3);
+
+        let other = List::new();
+        other.put_front(&nodes[1]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(other.len(), 1);
+
+        nodes[0].lock().unwrap().remove();
+        nodes[0].lock().unwrap().remove();
+        assert_eq!(list.len(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn subscribe_len() {
+        let list = List::new();
+        let mut len = list.subscribe_len();
+        assert_eq!(*len.borrow_and_update(), 0);
+
+        let node = list.push_back(());
+        len.changed().await.unwrap();
+        assert_eq!(*len.borrow_and_update(), 1);
+
+        node.lock().unwrap().remove();
+        len.changed().await.unwrap();
+        assert_eq!(*len.borrow_and_update(), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn subscribe_len_ignores_moves() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        let mut len = list.subscribe_len();
+        len.borrow_and_update();
+
+        list.reverse();
+        list.put_back(&nodes[2]);
+        list.put_front(&nodes[0]);
+        list.swap_head_tail();
+        assert!(!len.has_changed().unwrap());
+        assert_eq!(list.len(), 3);
+
+        // moving a node to another list changes both lengths
+        let other = List::new();
+        let mut other_len = other.subscribe_len();
+        other.put_back(&nodes[1]);
+        assert!(len.has_changed().unwrap());
+        assert_eq!(*len.borrow_and_update(), 2);
+        assert_eq!(*other_len.borrow_and_update(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn notify_when_empty() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3].map(|v| list.push_back(v)).into();
+        let empty = list.notify_when_empty();
+
+        let drainer = tokio::spawn({
+            let list = Arc::clone(&list);
+            async move {
+                while list.pop_front().is_some() {
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+        empty.await;
+        assert!(list.is_empty());
+        drainer.await.unwrap();
+        list.notify_when_empty().await;
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn notify_when_empty_ignores_moves() {
+        let list = List::new();
+        let node = list.push_back(1);
+        let empty = list.notify_when_empty();
+
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+        let mover = std::thread::spawn({
+            let list = Arc::clone(&list);
+            move || {
+                for _ in 0..10_000 {
+                    list.put_back(&node);
+                }
+                done_tx.send(node).ok();
+            }
+        });
+        let _node = tokio::select! {
+            biased;
+            () = empty => panic!("the list never became empty"),
+            node = done_rx => node.unwrap(),
+        };
+        mover.join().unwrap();
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn bump_to_front() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        assert!(!list.bump_to_front(&nodes[0]));
+        assert_eq!(contents(&list), ["a", "b", "c"]);
+        assert!(list.bump_to_front(&nodes[1]));
+        assert_eq!(contents(&list), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn for_each_node() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let mut handles = Vec::new();
+        list.for_each_node(|node| handles.push(node));
+        assert_eq!(handles.len(), nodes.len());
+        assert!(handles.iter().zip(&nodes).all(|(a, b)| Arc::ptr_eq(a, b)));
+        assert!(nodes.iter().all(|node| Arc::strong_count(node) == 2));
+
+        // moving nodes during the walk doesn't disrupt it
+        let mut seen = Vec::new();
+        list.for_each_node(|node| {
+            seen.push(node.lock().unwrap().data);
+            list.put_front(&node);
+        });
+        assert_eq!(seen, ["a", "b", "c"]);
+        assert_eq!(contents(&list), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn map_in_place() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3].map(|v| list.push_back(v)).into();
+
+        list.map_in_place(|x| *x *= 10).reverse();
+        assert_eq!(contents(&list), [30, 20, 10]);
+    }
+
+    #[test]
+    fn each_with_index_mut() {
+        let list = List::new();
+        let _nodes: Vec<_> = [9, 9, 9, 9].map(|v| list.push_back(v)).into();
+
+        list.each_with_index_mut(|i, x| *x = i);
+        assert_eq!(contents(&list), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn insert_at() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let _x = list.insert_at(1, "x").unwrap();
+        assert_eq!(contents(&list), ["a", "x", "b", "c"]);
+        assert!(list.insert_at(5, "y").is_none());
+        assert_eq!(contents(&list), ["a", "x", "b", "c"]);
+    }
+
+    #[test]
+    fn weak_count_of() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        // `a.next` and `c.prev`
+        assert_eq!(Node::weak_count_of(&nodes[1]), 2);
+        // `list.head`, its cached copy, and `b.prev`
+        assert_eq!(Node::weak_count_of(&nodes[0]), 3);
+        // `list.tail`, its cached copy, and `b.next`
+        assert_eq!(Node::weak_count_of(&nodes[2]), 3);
+
+        nodes[1].lock().unwrap().remove();
+        assert_eq!(Node::weak_count_of(&nodes[1]), 0);
+    }
+
+    #[test]
+    fn insert_at_ends() {
+        let list = List::new();
+        let _front = list.insert_at(0, "b").unwrap();
+        let _back = list.insert_at(list.len(), "d").unwrap();
+        let _middle = list.insert_at(1, "c").unwrap();
+        let _front = list.insert_at(0, "a").unwrap();
+        let _back = list.insert_at(4, "e").unwrap();
+        assert_eq!(contents(&list), ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn remove_at() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let removed = list.remove_at(0).unwrap();
+        assert!(Arc::ptr_eq(&removed, &nodes[0]));
+        assert_eq!(removed.lock().unwrap().data, "a");
+        assert_eq!(contents(&list), ["b", "c"]);
+        assert!(list.remove_at(2).is_none());
+    }
+
+    #[test]
+    fn remove_at_positions() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d", "e"].map(|v| list.push_back(v)).into();
+
+        assert!(Arc::ptr_eq(&list.remove_at(0).unwrap(), &nodes[0]));
+        assert_eq!(contents(&list), ["b", "c", "d", "e"]);
+        assert!(Arc::ptr_eq(&list.remove_at(3).unwrap(), &nodes[4]));
+        assert_eq!(contents(&list), ["b", "c", "d"]);
+        assert!(Arc::ptr_eq(&list.remove_at(1).unwrap(), &nodes[2]));
+        assert_eq!(contents(&list), ["b", "d"]);
+    }
+
+    #[test]
+    fn to_vec() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.to_vec(), ["a", "b", "c"]);
+        assert_eq!(*list.to_boxed_slice(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn iter_cloned() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b"].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.iter_cloned().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn find_node_by_id() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let id = Node::node_id(&nodes[1]);
+        assert!(Arc::ptr_eq(&list.find_node_by_id(id).unwrap(), &nodes[1]));
+        let unlinked = Node::new("d");
+        assert!(list.find_node_by_id(Node::node_id(&unlinked)).is_none());
+    }
+
+    #[test]
+    fn drain_rev() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let drained: Vec<_> = list
+            .drain_rev()
+            .map(|node| node.lock().unwrap().data)
+            .collect();
+        assert_eq!(drained, ["c", "b", "a"]);
+        assert!(contents(&list).is_empty());
+
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        let mut drain = list.drain();
+        assert_eq!(drain.next().unwrap().lock().unwrap().data, "a");
+        drop(drain);
+        assert!(contents(&list).is_empty());
+    }
+
+    #[test]
+    fn sorted_copy_by() {
+        let list = List::new();
+        let _nodes: Vec<_> = [3, 1, 2].map(|v| list.push_back(v)).into();
+
+        let (sorted, nodes) = list.sorted_copy_by(|a, b| a.cmp(b));
+        assert_eq!(contents(&sorted), [1, 2, 3]);
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(contents(&list), [3, 1, 2]);
+    }
+
+    #[test]
+    fn extend_front() {
+        let list = List::new();
+        let _x = list.push_back("x");
+
+        let nodes = list.extend_front(["a", "b", "c"]);
+        assert_eq!(contents(&list), ["c", "b", "a", "x"]);
+        assert_eq!(nodes[0].lock().unwrap().data, "a");
+    }
+
+    #[test]
+    fn iter_rev_from() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        let values: Vec<_> = List::iter_rev_from(&nodes[2])
+            .map(|node| node.lock().unwrap().data)
+            .collect();
+        assert_eq!(values, ["c", "b", "a"]);
+        let values: Vec<_> = List::iter_from(&nodes[2])
+            .map(|node| node.lock().unwrap().data)
+            .collect();
+        assert_eq!(values, ["c", "d"]);
+
+        let unlinked = Node::new("e");
+        assert!(List::iter_rev_from(&unlinked).next().is_none());
+        assert!(List::iter_from(&unlinked).next().is_none());
+    }
+
+    #[test]
+    fn coalesce() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 5, 6, 8].map(|v| list.push_back((v, v))).into();
+
+        list.coalesce(|&(start, end), &(next_start, next_end)| {
+            (end + 1 == next_start).then_some((start, next_end))
+        });
+        assert_eq!(contents(&list), [(1, 3), (5, 6), (8, 8)]);
+    }
+
+    #[test]
+    fn try_into_data() {
+        let list = List::new();
+        let node = list.push_back("a");
+        node.lock().unwrap().remove();
+
+        let clone = Arc::clone(&node);
+        let node = match Node::try_into_data(node) {
+            Ok(_) => panic!("node is still shared"),
+            Err(node) => node,
+        };
+        assert!(Arc::ptr_eq(&node, &clone));
+        drop(clone);
+        assert_eq!(Node::try_into_data(node).ok(), Some("a"));
+    }
+
+    #[test]
+    fn pop_front_if() {
+        let list = List::new();
+        let _nodes: Vec<_> = [2, 3].map(|v| list.push_back(v)).into();
+        let even = |x: &i32| x % 2 == 0;
+
+        assert_eq!(list.pop_front_if(even).unwrap().lock().unwrap().data, 2);
+        assert!(list.pop_front_if(even).is_none());
+        assert_eq!(contents(&list), [3]);
+    }
+
+    #[test]
+    fn lock_two() {
+        let a = Node::new(0);
+        let b = Node::new(0);
+        std::thread::scope(|scope| {
+            for (first, second) in [(&a, &b), (&b, &a)] {
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        let (mut first, mut second) = Node::lock_two(first, second);
+                        first.data += 1;
+                        second.data += 1;
+                    }
+                });
+            }
+        });
+        assert_eq!(a.lock().unwrap().data, 2000);
+        assert_eq!(b.lock().unwrap().data, 2000);
+    }
+
+    #[test]
+    fn checked_put_back() {
+        let list = List::new();
+        let other = List::new();
+        let nodes: Vec<_> = ["a", "b"].map(|v| list.push_back(v)).into();
+        let foreign = other.push_back("c");
+        let unlinked = Node::new("d");
+
+        assert_eq!(list.checked_put_back(&foreign), Err(NodeError::ForeignList));
+        assert_eq!(contents(&other), ["c"]);
+        assert_eq!(list.checked_put_back(&nodes[0]), Ok(()));
+        assert_eq!(list.checked_put_back(&unlinked), Ok(()));
+        assert_eq!(contents(&list), ["b", "a", "d"]);
+    }
+
+    #[test]
+    fn pairs() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let pairs: Vec<_> = list
+            .pairs()
+            .map(|(a, b)| (a.lock().unwrap().data, b.lock().unwrap().data))
+            .collect();
+        assert_eq!(pairs, [("a", "b"), ("b", "c")]);
+
+        let single = List::new();
+        let _a = single.push_back("a");
+        assert!(single.pairs().next().is_none());
+    }
+
+    #[test]
+    fn clone_handles() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let handles = list.clone_handles();
+        assert!(handles.iter().zip(&nodes).all(|(a, b)| Arc::ptr_eq(a, b)));
+        assert!(nodes.iter().all(|node| Arc::strong_count(node) == 2));
+        drop(handles);
+        assert!(nodes.iter().all(|node| Arc::strong_count(node) == 1));
+    }
+
+    #[test]
+    fn count_both_directions() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+        assert_eq!(list.count_both_directions(), (4, 4));
+
+        // skip `c` going forward, but not going backward
+        nodes[1].lock().unwrap().set_next(Some(&nodes[3]));
+        assert_eq!(list.count_both_directions(), (3, 4));
+    }
+
+    #[test]
+    fn drain_where() {
+        let list = List::new();
+        let _nodes: Vec<_> = [-1, 2, -3, 4].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.drain_where(|x| *x < 0), [-1, -3]);
+        assert_eq!(contents(&list), [2, 4]);
+    }
+
+    #[test]
+    fn filter_in_place() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.filter_in_place(|x| x % 2 == 0), 3);
+        assert_eq!(contents(&list), [2, 4]);
+        list.retain(|&x| x > 2);
+        assert_eq!(contents(&list), [4]);
+    }
+
+    #[test]
+    fn remove_many() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d", "e", "f"]
+            .map(|v| list.push_back(v))
+            .into();
+        let other = List::new();
+        let foreign = other.push_back("x");
+
+        list.remove_many(&[
+            Arc::clone(&nodes[4]),
+            Arc::clone(&nodes[0]),
+            Arc::clone(&foreign),
+            Arc::clone(&nodes[2]),
+        ]);
+        assert_eq!(contents(&list), ["b", "d", "f"]);
+        assert_eq!(contents(&other), ["x"]);
+        assert!(!nodes[0].lock().unwrap().is_linked());
+    }
+
+    #[test]
+    fn set_links() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        // `c` points back to `a` in both directions
+        nodes[2].lock().unwrap().set_next(Some(&nodes[0]));
+        nodes[0].lock().unwrap().set_prev(Some(&nodes[2]));
+        assert!(list.has_cycle());
+
+        nodes[2].lock().unwrap().set_next(None);
+        nodes[0].lock().unwrap().set_prev(None);
+        assert!(!list.has_cycle());
+
+        let other = List::new();
+        nodes[1].lock().unwrap().set_parent(Some(&other));
+        assert!(!list.contains_node(&nodes[1]));
+        assert!(other.contains_node(&nodes[1]));
+    }
+
+    #[test]
+    fn data_guard() {
+        let list = List::new();
+        let node = list.push_back(1);
+
+        assert_eq!(*node.data_guard(), 1);
+        *node.data_guard() += 1;
+        assert_eq!(contents(&list), [2]);
+    }
+
+    #[test]
+    fn head_cache() {
+        let list = List::new();
+        let locked_head = |list: &Arc<List<_>>| list.head.lock().unwrap().upgrade();
+        let same = |a: Option<ArcNode<_>>, b: Option<ArcNode<_>>| {
+            a.map(|a| Arc::as_ptr(&a)) == b.map(|b| Arc::as_ptr(&b))
+        };
+        assert!(list.head_cache.load().is_none());
+
+        let a = list.push_back("a");
+        let b = list.push_front("b");
+        assert!(same(list.head_cache.load(), locked_head(&list)));
+        assert!(Arc::ptr_eq(&list.head().unwrap(), &b));
+
+        b.lock().unwrap().remove();
+        assert!(same(list.head_cache.load(), locked_head(&list)));
+        assert!(Arc::ptr_eq(&list.head().unwrap(), &a));
+
+        let c = list.insert_before(&a, "c");
+        assert!(same(list.head_cache.load(), Some(c)));
+
+        list.pop_front();
+        list.pop_front();
+        assert!(list.head_cache.load().is_none());
+        assert!(list.head().is_none());
+    }
+
+    #[test]
+    fn validate_and_repair() {
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+        assert_eq!(list.validate_and_repair(), Ok(()));
+
+        // dropping the last handle leaves `c.next` and the tail dangling
+        nodes.pop();
+        let report = list.validate_and_repair().unwrap_err();
+        assert!(report.is_repaired());
+        assert!(report.repaired.contains(&Issue::DanglingNext { index: 2 }));
+        assert!(report.repaired.contains(&Issue::StaleTail));
+        assert!(report.repaired.contains(&Issue::LengthMismatch {
+            recorded: 4,
+            actual: 3
+        }));
+        assert_eq!(contents(&list), ["a", "b", "c"]);
+        assert_eq!(list.validate_and_repair(), Ok(()));
+
+        // dropping a middle node cuts the chain, and the rest is stitched back in from the tail
+        nodes.remove(1);
+        let report = list.validate_and_repair().unwrap_err();
+        assert!(report.is_repaired());
+        assert!(report.repaired.contains(&Issue::DanglingNext { index: 0 }));
+        assert!(report.repaired.contains(&Issue::StalePrev { index: 1 }));
+        assert_eq!(contents(&list), ["a", "c"]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.validate_and_repair(), Ok(()));
+    }
+
+    #[test]
+    fn validate_and_repair_cut() {
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+        nodes.remove(1);
+        let report = list.validate_and_repair().unwrap_err();
+        assert!(report.is_repaired());
+        assert_eq!(contents(&list), ["a", "c", "d"]);
+        assert_eq!(list.len(), 3);
+
+        nodes[1].lock().unwrap().remove();
+        assert_eq!(list.len(), 2);
+        assert_eq!(contents(&list), ["a", "d"]);
+        assert_eq!(list.validate_and_repair(), Ok(()));
+    }
+
+    #[test]
+    fn validate_and_repair_stale_head() {
+        // the tail's parent being unset doesn't stop the walk back from it
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        nodes[2].lock().unwrap().set_parent(None);
+        nodes.remove(0);
+        let report = list.validate_and_repair().unwrap_err();
+        assert!(report.is_repaired());
+        assert!(report.repaired.contains(&Issue::StaleHead));
+        assert!(report.repaired.contains(&Issue::StaleParent { index: 1 }));
+        assert_eq!(contents(&list), ["b", "c"]);
+
+        // a cycle in the `prev` links is reported instead of followed forever
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        let c = Arc::clone(&nodes[2]);
+        nodes[1].lock().unwrap().set_prev(Some(&c));
+        nodes.remove(0);
+        let report = list.validate_and_repair().unwrap_err();
+        assert!(!report.is_repaired());
+        assert!(report.unrepaired.contains(&Issue::Cycle));
+    }
+
+    #[test]
+    fn circular() {
+        let list = List::new_circular();
+        assert!(list.is_circular());
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+        assert!(Arc::ptr_eq(
+            &nodes[3].lock().unwrap().next().unwrap(),
+            &nodes[0]
+        ));
+        assert!(Arc::ptr_eq(
+            &nodes[0].lock().unwrap().prev().unwrap(),
+            &nodes[3]
+        ));
+        assert!(!list.has_cycle());
+
+        list.rotate_nodes(5);
+        assert_eq!(contents(&list), ["b", "c", "d", "a"]);
+
+        // walking by hand wraps around, so it has to be bounded by the length
+        let mut walked = Vec::new();
+        let mut node = list.head().unwrap();
+        for _ in 0..list.len() * 2 {
+            let next = {
+                let lock = node.lock().unwrap();
+                walked.push(lock.data);
+                lock.next().unwrap()
+            };
+            node = next;
         }
+        assert_eq!(walked, ["b", "c", "d", "a", "b", "c", "d", "a"]);
 
-        for node in nodes.iter().rev() {
-            list.put_back(node);
+        let single = List::new_circular();
+        let only = single.push_back("x");
+        assert!(Arc::ptr_eq(&only.lock().unwrap().next().unwrap(), &only));
+        assert!(List::<&str>::new()
+            .push_back("y")
+            .lock()
+            .unwrap()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn rotate_nodes() {
+        for len in 1..5 {
+            for n in 0..10 {
+                let list = List::new();
+                let _nodes: Vec<_> = (0..len).map(|v| list.push_back(v)).collect();
+                list.rotate_nodes(n);
+                let expected: Vec<_> = (0..len).map(|i| (i + n) % len).collect();
+                assert_eq!(contents(&list), expected);
+            }
         }
+    }
 
-        let mut node = list.head();
-        for v in values.iter().rev() {
-            let temp = node.unwrap();
-            let this = temp.lock().unwrap();
-            assert_eq!(&this.data, v);
-            node = this.next();
+    #[test]
+    fn circular_wrap_while_removing() {
+        let list = List::new_circular();
+        let head = list.push_back("a");
+        let tail = list.push_back("b");
+
+        let head_lock = head.lock().unwrap();
+        std::thread::scope(|scope| {
+            // this locks the list's tail, then waits for `head`
+            let remover = scope.spawn(|| tail.lock().unwrap().remove());
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            // wrapping around to the tail must not wait for the list's tail lock
+            assert!(Arc::ptr_eq(&head_lock.prev().unwrap(), &tail));
+            drop(head_lock);
+            remover.join().unwrap();
+        });
+        assert_eq!(contents(&list), ["a"]);
+        assert!(Arc::ptr_eq(&head.lock().unwrap().prev().unwrap(), &head));
+    }
+
+    #[test]
+    fn queue() {
+        let mut queue = Queue::new();
+        assert!(queue.is_empty());
+        queue.push(1);
+        queue.push(2);
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(3);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn with_nodes() {
+        let list = List::with_nodes(vec![1, 2, 3]);
+        assert_eq!(contents(&list), [1, 2, 3]);
+        assert_eq!(list.iter().count(), 3);
+
+        let handles = list.take_handles();
+        assert_eq!(handles.len(), 3);
+        assert!(list.take_handles().is_empty());
+        assert_eq!(contents(&list), [1, 2, 3]);
+    }
+
+    #[test]
+    fn deque() {
+        let mut deque = Deque::new();
+        assert!(deque.front().is_none());
+        deque.push_back(2);
+        deque.push_front(1);
+        deque.push_back(3);
+        assert_eq!(*deque.front().unwrap(), 1);
+        assert_eq!(*deque.back().unwrap(), 3);
+        assert_eq!(deque.len(), 3);
+
+        // popping from the end that was pushed to is LIFO
+        deque.push_back(4);
+        assert_eq!(deque.pop_back(), Some(4));
+        // popping from the other end is FIFO
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_back(), None);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn into_iter_data() {
+        let list = List::with_nodes(vec![1, 2, 3]);
+        let data: Vec<_> = list.into_iter_data().map(Result::ok).collect();
+        assert_eq!(data, [Some(1), Some(2), Some(3)]);
+
+        // a node with a handle held elsewhere is handed back instead
+        let list = List::new();
+        let a = list.push_back("a");
+        let mut iter = list.into_iter_data();
+        assert!(Arc::ptr_eq(&iter.next().unwrap().unwrap_err(), &a));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let list = List::with_nodes(vec![1, 2, 3, 4]);
+        let removed = list.remove_at(1).unwrap();
+        let weak = Arc::downgrade(&removed);
+        drop(removed);
+        // the list still keeps the removed node alive
+        assert!(weak.upgrade().is_some());
+
+        list.shrink_to_fit();
+        assert!(weak.upgrade().is_none());
+        assert_eq!(contents(&list), [1, 3, 4]);
+        assert_eq!(list.take_handles().len(), 3);
+    }
+
+    #[test]
+    fn singleton() {
+        let (list, node) = List::singleton("a");
+        assert_eq!(list.len(), 1);
+        assert!(Arc::ptr_eq(&list.head().unwrap(), &node));
+        assert!(Arc::ptr_eq(&list.tail().unwrap(), &node));
+    }
+
+    #[test]
+    fn map_while() {
+        let list = List::new();
+        let _nodes: Vec<_> = ["1", "2", "x", "3"].map(|v| list.push_back(v)).into();
+
+        let (parsed, nodes) = list.map_while(|s| s.parse::<i32>().ok());
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(contents(&parsed), [1, 2]);
+        assert_eq!(contents(&list), ["1", "2", "x", "3"]);
+    }
+
+    #[test]
+    fn scan() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3].map(|v| list.push_back(v)).into();
+
+        let (sums, _sum_nodes) = list.scan(0, |sum, x| {
+            *sum += x;
+            Some(*sum)
+        });
+        assert_eq!(contents(&sums), [1, 3, 6]);
+    }
+
+    #[test]
+    fn detach() {
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let b = nodes.remove(1);
+        assert_eq!(b.detach(), Some("b"));
+        assert_eq!(contents(&list), ["a", "c"]);
+
+        let a = Arc::clone(&nodes[0]);
+        assert_eq!(a.detach(), None);
+        assert!(!nodes[0].lock().unwrap().is_linked());
+        assert_eq!(contents(&list), ["c"]);
+    }
+
+    #[test]
+    fn retain_between() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3, 4, 5, 6].map(|v| list.push_back(v)).into();
+
+        list.retain_between(&nodes[1], &nodes[4], |x| x % 2 == 0);
+        assert_eq!(contents(&list), [1, 2, 4, 6]);
+    }
+
+    #[test]
+    fn extend_nodes() {
+        let first = List::new();
+        let _nodes: Vec<_> = ["a", "b"].map(|v| first.push_back(v)).into();
+        let second = List::new();
+        let _c = second.push_back("c");
+
+        let nodes = second.extend_nodes(first.drain().collect::<Vec<_>>());
+        assert_eq!(nodes.len(), 2);
+        assert!(contents(&first).is_empty());
+        assert_eq!(contents(&second), ["c", "a", "b"]);
+        assert!(second.contains_node(&nodes[0]));
+    }
+
+    #[test]
+    fn data_ptr() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b"].map(|v| list.push_back(v)).into();
+        let clone = Arc::clone(&nodes[0]);
+
+        assert_eq!(Node::data_ptr(&clone), Node::data_ptr(&nodes[0]));
+        assert_ne!(Node::data_ptr(&nodes[0]), Node::data_ptr(&nodes[1]));
+
+        let mut table = std::collections::HashMap::new();
+        table.insert(NodeKey::new(&nodes[0]), 1);
+        table.insert(NodeKey::new(&nodes[1]), 2);
+        assert_eq!(table[&NodeKey::new(&clone)], 1);
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn from_vec() {
+        // deliberately not `Clone`
+        #[derive(Debug, PartialEq)]
+        struct Item(i32);
+
+        let (list, nodes) = List::from_vec(vec![Item(1), Item(2), Item(3)]);
+        assert_eq!(list.len(), 3);
+        assert!(list.iter().zip(&nodes).all(|(a, b)| Arc::ptr_eq(&a, b)));
+        let items: Vec<_> = nodes.into_iter().map(|node| node.detach()).collect();
+        assert_eq!(items, [Some(Item(1)), Some(Item(2)), Some(Item(3))]);
+    }
+
+    #[test]
+    fn iter_len() {
+        let list = List::new();
+        assert_eq!(list.iter().len(), 0);
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 4);
+        iter.next();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.len(), iter.count());
+
+        assert_eq!(List::iter_from(&nodes[1]).count(), 3);
+        assert_eq!(List::iter_from(&Node::new("e")).count(), 0);
+    }
+
+    #[test]
+    fn update() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3].map(|v| list.push_back(v)).into();
+
+        Node::update(&nodes[1], |x| x + 10);
+        assert_eq!(contents(&list), [1, 12, 3]);
+    }
+
+    #[test]
+    fn update_panic() {
+        let node = Node::new(String::from("a"));
+        let handle = Arc::clone(&node);
+        let result = std::thread::spawn(move || Node::update(&handle, |_| panic!())).join();
+        assert!(result.is_err());
+        let lock = node
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert_eq!(lock.data, "");
+    }
+
+    #[test]
+    fn swap_lists() {
+        let a = List::new();
+        let a_nodes: Vec<_> = ["a", "b"].map(|v| a.push_back(v)).into();
+        let b = List::new();
+        let b_nodes: Vec<_> = ["x", "y", "z"].map(|v| b.push_back(v)).into();
+
+        List::swap_lists(&a, &b);
+        assert_eq!(contents(&a), ["x", "y", "z"]);
+        assert_eq!(contents(&b), ["a", "b"]);
+        for node in &a_nodes {
+            assert!(List::same_list(&node.lock().unwrap().parent().unwrap(), &b));
         }
-        assert!(node.is_none());
+        for node in &b_nodes {
+            assert!(List::same_list(&node.lock().unwrap().parent().unwrap(), &a));
+        }
+
+        // operations on the swapped lists see their new contents
+        b.pop_front();
+        let _w = a.push_back("w");
+        assert_eq!(contents(&a), ["x", "y", "z", "w"]);
+        assert_eq!(contents(&b), ["b"]);
+    }
+
+    #[test]
+    fn swap_lists_concurrent_remove() {
+        for _ in 0..20 {
+            let a = List::new();
+            let b = List::new();
+            let nodes: Vec<_> = (0..50)
+                .map(|i| a.push_back(i))
+                .chain((50..100).map(|i| b.push_back(i)))
+                .collect();
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    for _ in 0..200 {
+                        List::swap_lists(&a, &b);
+                    }
+                });
+                scope.spawn(|| {
+                    for node in &nodes {
+                        node.lock().unwrap().remove();
+                    }
+                });
+            });
+            assert_eq!((a.len(), b.len()), (0, 0));
+            assert!(a.head().is_none() && a.tail().is_none());
+            assert!(b.head().is_none() && b.tail().is_none());
+        }
+    }
+
+    #[test]
+    fn compare_neighbors_concurrent_remove() {
+        for _ in 0..20 {
+            let list = List::new();
+            let nodes: Vec<_> = (0..100).map(|i| list.push_back(i)).collect();
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    while !list.is_empty() {
+                        assert!(list.is_sorted());
+                        list.max();
+                        list.coalesce(|_, _| None);
+                    }
+                });
+                scope.spawn(|| {
+                    for node in nodes.iter().rev() {
+                        node.lock().unwrap().remove();
+                    }
+                });
+            });
+            assert!(list.head().is_none() && list.tail().is_none());
+        }
+    }
+
+    #[test]
+    fn move_after() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        list.move_after(&nodes[3], &nodes[0]);
+        assert_eq!(contents(&list), ["a", "d", "b", "c"]);
+        list.move_after(&nodes[3], &nodes[0]);
+        assert_eq!(contents(&list), ["a", "d", "b", "c"]);
+        list.move_after(&nodes[0], &nodes[2]);
+        assert_eq!(contents(&list), ["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn split_into_chunks() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        let chunks = list.split_into_chunks(2);
+        assert!(contents(&list).is_empty());
+        let chunk_contents: Vec<_> = chunks.iter().map(contents).collect();
+        assert_eq!(chunk_contents, [vec![1, 2], vec![3, 4], vec![5]]);
+        assert!(chunks[1].contains_node(&nodes[3]));
+    }
+
+    #[test]
+    fn drain_between() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        let drained = list.drain_between(&nodes[0], &nodes[3]);
+        assert_eq!(drained.len(), 2);
+        assert!(Arc::ptr_eq(&drained[0], &nodes[1]));
+        assert!(Arc::ptr_eq(&drained[1], &nodes[2]));
+        assert_eq!(contents(&list), ["a", "d"]);
+        assert!(list.drain_between(&nodes[0], &nodes[3]).is_empty());
+    }
+
+    #[test]
+    fn gc_dangling() {
+        let list = List::new();
+        let mut nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        // dropping the handle of a middle node cuts the forward chain off after `a`
+        nodes.remove(1);
+        assert_eq!(list.iter().count(), 1);
+
+        list.gc_dangling();
+        assert_eq!(contents(&list), ["a", "c", "d"]);
+        list.gc_dangling();
+        assert_eq!(contents(&list), ["a", "c", "d"]);
+    }
+
+    #[test]
+    fn gc_dangling_cycle() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+        let a = Arc::clone(&nodes[0]);
+        nodes[2].lock().unwrap().set_next(Some(&a));
+        assert!(list.has_cycle());
+
+        list.gc_dangling();
+        assert!(!list.has_cycle());
+        assert_eq!(contents(&list), ["a", "b", "c"]);
+        assert_eq!(list.validate_and_repair(), Ok(()));
+    }
+
+    #[test]
+    fn uncons() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let (head, rest) = list.uncons().unwrap();
+        assert!(Arc::ptr_eq(&head, &nodes[0]));
+        assert!(!head.lock().unwrap().is_linked());
+        assert!(List::same_list(&rest, &list));
+        assert_eq!(contents(&list), ["b", "c"]);
+        assert!(List::<()>::new().uncons().is_none());
+    }
+
+    #[test]
+    fn parent_ptr() {
+        let a = List::new();
+        let b = List::new();
+        let nodes = [
+            a.push_back(1),
+            b.push_back(2),
+            a.push_back(3),
+            b.push_back(4),
+        ];
+
+        let mut groups = std::collections::HashMap::<_, Vec<_>>::new();
+        for node in &nodes {
+            groups
+                .entry(Node::parent_ptr(node))
+                .or_default()
+                .push(node.lock().unwrap().data);
+        }
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&Arc::as_ptr(&a)], [1, 3]);
+        assert_eq!(groups[&Arc::as_ptr(&b)], [2, 4]);
+    }
+
+    #[test]
+    fn reverse_in_chunks() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        list.reverse_in_chunks(2);
+        assert_eq!(contents(&list), [2, 1, 4, 3, 5]);
+        list.reverse_in_chunks(3);
+        assert_eq!(contents(&list), [4, 1, 2, 5, 3]);
+    }
+
+    #[test]
+    fn try_with_all_locked() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c"].map(|v| list.push_back(v)).into();
+
+        let data = list.try_with_all_locked(|guards| {
+            guards.iter().map(|guard| guard.data).collect::<Vec<_>>()
+        });
+        assert_eq!(data.unwrap(), ["a", "b", "c"]);
+
+        let held = nodes[1].lock().unwrap();
+        assert!(list.try_with_all_locked(|_| ()).is_none());
+        drop(held);
+        assert!(list.try_with_all_locked(|_| ()).is_some());
+
+        // a held endpoint makes it give up instead of blocking
+        let held = list.tail.lock().unwrap();
+        assert!(list.try_with_all_locked(|_| ()).is_none());
+        drop(held);
+        let held = list.head.lock().unwrap();
+        assert!(list.try_with_all_locked(|_| ()).is_none());
+        drop(held);
+    }
+
+    #[test]
+    fn span_len() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b", "c", "d"].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.span_len(&nodes[1], &nodes[3]), Some(3));
+        assert_eq!(list.span_len(&nodes[2], &nodes[2]), Some(1));
+        assert_eq!(list.span_len(&nodes[3], &nodes[1]), None);
+        assert_eq!(List::new().span_len(&nodes[0], &nodes[1]), None);
+    }
+
+    #[test]
+    fn next_eligible() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+        let odd = |x: &i32| x % 2 == 1;
+
+        let picked: Vec<_> = (0..6)
+            .map(|_| list.next_eligible(odd).unwrap().lock().unwrap().data)
+            .collect();
+        assert_eq!(picked, [1, 3, 5, 1, 3, 5]);
+        assert_eq!(contents(&list), [2, 4, 1, 3, 5]);
+
+        assert!(list.next_eligible(|&x| x > 10).is_none());
+        assert_eq!(contents(&list), [2, 4, 1, 3, 5]);
+    }
+
+    #[test]
+    fn debug_topology() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b"].map(|v| list.push_back(v)).into();
+        let [a, b] = [&nodes[0], &nodes[1]].map(|node| format!("{:p}", Arc::as_ptr(node)));
+
+        assert_eq!(
+            list.debug_topology(),
+            format!("head -> {a}\nnone <- {a} -> {b}\n{a} <- {b} -> none\ntail -> {b}\n")
+        );
+
+        let held = nodes[1].lock().unwrap();
+        assert!(list.debug_topology().contains(&format!("{b}: locked")));
+        drop(held);
+
+        let held = list.tail.lock().unwrap();
+        assert_eq!(
+            list.debug_topology(),
+            format!("head -> {a}\nnone <- {a} -> {b}\n{a} <- {b} -> none\ntail -> <locked>\n")
+        );
+        drop(held);
+        let held = list.head.lock().unwrap();
+        assert_eq!(
+            list.debug_topology(),
+            format!("head -> <locked>\ntail -> {b}\n")
+        );
+        drop(held);
+    }
+
+    #[test]
+    fn reserve_nodes() {
+        let list = List::new();
+        list.reserve_nodes(100);
+        let mut nodes = Vec::with_capacity(100);
+
+        let before = allocations();
+        for i in 0..100 {
+            nodes.push(list.push_back(i));
+        }
+        assert_eq!(allocations(), before);
+        assert_eq!(contents(&list), (0..100).collect::<Vec<_>>());
+
+        list.reserve_nodes(10);
+        list.shrink_to_fit();
+        assert!(list.pool.lock().unwrap().is_empty());
+        assert_eq!(contents(&list), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn each_node_with_neighbors() {
+        let list = List::new();
+        let _nodes: Vec<_> = [3, 6, 9, 12].map(|v| list.push_back(v)).into();
+
+        let mut smoothed = Vec::new();
+        list.each_node_with_neighbors(|prev, node, next| {
+            let window: Vec<i32> = [prev, Some(node), next]
+                .into_iter()
+                .flatten()
+                .map(|node| node.lock().unwrap().data)
+                .collect();
+            smoothed.push(window.iter().sum::<i32>() / window.len() as i32);
+        });
+        assert_eq!(smoothed, [4, 6, 9, 10]);
+    }
+
+    #[test]
+    fn rebuild_sorted() {
+        let list = List::new();
+        let nodes: Vec<_> = [3, 1, 4, 1, 5].map(|v| list.push_back(v)).into();
+        let other = List::new();
+        let moved = other.push_back(2);
+
+        let mut scrambled = nodes.clone();
+        scrambled.swap(0, 4);
+        scrambled.push(Arc::clone(&moved));
+        let sorted = list.rebuild_sorted(scrambled);
+        assert_eq!(contents(&list), [1, 1, 2, 3, 4, 5]);
+        assert!(contents(&other).is_empty());
+        assert!(sorted
+            .iter()
+            .zip(list.iter())
+            .all(|(a, b)| Arc::ptr_eq(a, &b)));
+    }
+
+    #[test]
+    fn count_while() {
+        let list = List::new();
+        let _nodes: Vec<_> = [1, 2, 3, 4, 1].map(|v| list.push_back(v)).into();
+
+        assert_eq!(list.count_while(|&x| x < 3), 2);
+        assert_eq!(list.count_while(|&x| x > 3), 0);
+        assert_eq!(list.count_while(|_| true), 5);
+    }
+
+    #[test]
+    fn group_by_key() {
+        let list = List::new();
+        let nodes: Vec<_> = [1, 2, 3, 4, 5].map(|v| list.push_back(v)).into();
+
+        let groups = list.group_by_key(|x| x % 2 == 0);
+        assert_eq!(groups.len(), 2);
+        let data = |nodes: &[ArcNode<i32>]| {
+            nodes
+                .iter()
+                .map(|node| node.lock().unwrap().data)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(data(&groups[&false]), [1, 3, 5]);
+        assert_eq!(data(&groups[&true]), [2, 4]);
+        assert!(Arc::ptr_eq(&groups[&true][0], &nodes[1]));
+    }
+
+    #[test]
+    fn insert_many_after() {
+        let list = List::new();
+        let nodes: Vec<_> = ["a", "b"].map(|v| list.push_back(v)).into();
+
+        let inserted = list.insert_many_after(&nodes[0], ["x", "y"]);
+        assert_eq!(inserted.len(), 2);
+        assert_eq!(contents(&list), ["a", "x", "y", "b"]);
+
+        let _at_tail = list.insert_many_after(&nodes[1], ["z"]);
+        assert_eq!(contents(&list), ["a", "x", "y", "b", "z"]);
     }
 }
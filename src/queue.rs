@@ -0,0 +1,55 @@
+//! A first-in, first-out queue built on [`List`].
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::deque::into_data;
+use crate::{ArcNode, List};
+
+/// A first-in, first-out queue backed by a [`List`].
+///
+/// Unlike the list itself, the queue keeps the handle of every node it contains, so values can't
+/// be lost by dropping a handle returned from [`List::push_back`].
+pub struct Queue<T> {
+    list: Arc<List<T>>,
+    /// The handles of the nodes in `list`, in the same order.
+    nodes: VecDeque<ArcNode<T>>,
+}
+
+impl<T> Queue<T> {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self {
+            list: List::new(),
+            nodes: VecDeque::new(),
+        }
+    }
+
+    /// Returns the number of values in the queue.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Checks whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Adds a value to the back of the queue.
+    pub fn push(&mut self, data: T) {
+        self.nodes.push_back(self.list.push_back(data));
+    }
+
+    /// Removes the value at the front of the queue, returning it.
+    pub fn pop(&mut self) -> Option<T> {
+        let node = self.list.pop_front()?;
+        let owned = self.nodes.pop_front();
+        Some(into_data(node, owned))
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,313 @@
+//! Integrity checking, repair, and diagnostics for [`List`].
+
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Weak};
+
+use crate::{lock, ArcNode, List, WeakNode};
+
+/// A problem found in the links of a [`List`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    /// Following `next` pointers from the head revisits a node.
+    Cycle,
+    /// The list's `head` pointed to a node which has been dropped.
+    StaleHead,
+    /// The list's `tail` did not point to the last node reachable from the head.
+    StaleTail,
+    /// The node at `index` had a `next` pointer to a node which has been dropped.
+    DanglingNext { index: usize },
+    /// The node at `index` had a `next` pointer which did not point to the node after it.
+    StaleNext { index: usize },
+    /// The node at `index` had a `prev` pointer which did not point to the node before it.
+    StalePrev { index: usize },
+    /// The node at `index` did not have this list as its parent.
+    StaleParent { index: usize },
+    /// The recorded length of the list did not match the number of reachable nodes.
+    LengthMismatch { recorded: usize, actual: usize },
+}
+
+/// The result of [`List::validate_and_repair`], listing the problems found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Problems which were fixed.
+    pub repaired: Vec<Issue>,
+    /// Problems which could not be fixed.
+    pub unrepaired: Vec<Issue>,
+}
+
+impl RepairReport {
+    /// Checks whether every problem found was fixed.
+    pub fn is_repaired(&self) -> bool {
+        self.unrepaired.is_empty()
+    }
+}
+
+/// Walks backward from `last` along the raw `prev` links, returning the first node reached, or
+/// `None` if the walk runs into a cycle.
+// lock order:
+//  each node, one at a time {}
+fn first_before<T>(last: ArcNode<T>) -> Option<ArcNode<T>> {
+    let mut seen = HashSet::new();
+    let mut node = last;
+    while seen.insert(Arc::as_ptr(&node)) {
+        let prev = lock(&node).prev.upgrade();
+        match prev {
+            Some(prev) => node = prev,
+            None => return Some(node),
+        }
+    }
+    None
+}
+
+/// Checks whether `weak` once pointed to a node which has since been dropped.
+fn is_dangling<T>(weak: &Weak<T>) -> bool {
+    weak.strong_count() == 0 && !Weak::ptr_eq(weak, &Weak::new())
+}
+
+impl<T> List<T> {
+    /// Checks the links of the list for inconsistencies, fixing the ones it can.
+    ///
+    /// The forward chain from the head is taken as the source of truth: `prev` and `parent`
+    /// pointers along it are corrected, and the tail and length are reset to match it. If the
+    /// head itself was dropped, the chain is recovered by walking backward from the tail instead.
+    /// A `next` pointer to a dropped node cuts the chain, so the nodes after the cut are found by
+    /// walking backward from the tail and stitched back in, as in
+    /// [`gc_dangling`](Self::gc_dangling). A list containing a cycle in either direction is
+    /// reported but left untouched.
+    ///
+    /// Returns `Ok(())` if no problems were found. This is a maintenance operation, and should not
+    /// be run while the list is being modified concurrently.
+    // lock order:
+    //  as in `has_cycle`
+    //  self.tail {
+    //    self.head {}
+    //  }
+    //  each node, one at a time {}
+    //  self.head {}
+    //  each node, one at a time {}
+    //  self.tail {}
+    pub fn validate_and_repair(self: &Arc<Self>) -> Result<(), RepairReport> {
+        let mut report = RepairReport::default();
+        if self.has_cycle() {
+            report.unrepaired.push(Issue::Cycle);
+            return Err(report);
+        }
+
+        let (head, last) = {
+            let tail = lock(&self.tail);
+            let head = lock(&self.head).clone();
+            (head, tail.upgrade())
+        };
+        let first = if is_dangling(&head) {
+            let first = match last.clone().map(first_before) {
+                Some(None) => {
+                    report.unrepaired.push(Issue::StaleHead);
+                    report.unrepaired.push(Issue::Cycle);
+                    return Err(report);
+                }
+                first => first.flatten(),
+            };
+            report.repaired.push(Issue::StaleHead);
+            let mut head = lock(&self.head);
+            self.set_head(
+                &mut head,
+                first.as_ref().map_or_else(Weak::new, Arc::downgrade),
+            );
+            first
+        } else {
+            head.upgrade()
+        };
+
+        let mut seen = HashSet::new();
+        let mut chain: Vec<ArcNode<T>> = first
+            .map(|first| {
+                std::iter::successors(Some(first), |node| lock(node).next.upgrade())
+                    .inspect(|node| {
+                        seen.insert(Arc::as_ptr(node));
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let cut = chain
+            .last()
+            .is_some_and(|node| is_dangling(&lock(node).next));
+        if cut {
+            let mut rest: Vec<ArcNode<T>> = last
+                .map(|last| {
+                    std::iter::successors(Some(last), |node| lock(node).prev.upgrade())
+                        .take_while(|node| seen.insert(Arc::as_ptr(node)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            rest.reverse();
+            chain.append(&mut rest);
+        }
+
+        for (index, node) in chain.iter().enumerate() {
+            let mut lock = lock(node);
+            let expected_prev: WeakNode<T> = index
+                .checked_sub(1)
+                .map_or_else(Weak::new, |i| Arc::downgrade(&chain[i]));
+            if !Weak::ptr_eq(&lock.prev, &expected_prev) {
+                report.repaired.push(Issue::StalePrev { index });
+                lock.prev = expected_prev;
+            }
+            if !lock.parent_is(self) {
+                report.repaired.push(Issue::StaleParent { index });
+                lock.parent = Arc::downgrade(self);
+            }
+            let expected_next: WeakNode<T> =
+                chain.get(index + 1).map_or_else(Weak::new, Arc::downgrade);
+            if !Weak::ptr_eq(&lock.next, &expected_next) {
+                if is_dangling(&lock.next) {
+                    report.repaired.push(Issue::DanglingNext { index });
+                } else {
+                    report.repaired.push(Issue::StaleNext { index });
+                }
+                lock.next = expected_next;
+            }
+        }
+        let count = chain.len();
+
+        let mut tail = lock(&self.tail);
+        let last = chain.last().map_or_else(Weak::new, Arc::downgrade);
+        if !Weak::ptr_eq(&tail, &last) {
+            report.repaired.push(Issue::StaleTail);
+            self.set_tail(&mut tail, last);
+        }
+        drop(tail);
+
+        let recorded = self.len.swap(count, Ordering::Relaxed);
+        if recorded != count {
+            report.repaired.push(Issue::LengthMismatch {
+                recorded,
+                actual: count,
+            });
+            self.notify_len();
+        }
+
+        if report.repaired.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+
+    /// Relinks the nodes which can still be reached from the ends of the list, skipping over any
+    /// which have been dropped.
+    ///
+    /// A dropped node leaves its neighbors with dangling links, which cut the list in two. This
+    /// walks forward from the head and backward from the tail as far as the links allow, then
+    /// joins the two halves back together. If several nodes in different places were dropped,
+    /// any nodes stranded between them can't be found, and are left out of the list. Each walk
+    /// stops at the first node it has already visited, so a cycle is cut where it closes.
+    ///
+    /// This is a maintenance operation, and should not be run while the list is being modified
+    /// concurrently.
+    // lock order:
+    //  self.head {}
+    //  self.tail {}
+    //  each node, one at a time {}
+    //  each node, one at a time {}
+    //  self.tail {
+    //    self.head {}
+    //  }
+    pub fn gc_dangling(self: &Arc<Self>) {
+        let first = lock(&self.head).upgrade();
+        let last = lock(&self.tail).upgrade();
+        let mut seen = HashSet::new();
+        let mut chain: Vec<ArcNode<T>> = first
+            .map(|first| {
+                std::iter::successors(Some(first), |node| lock(node).next.upgrade())
+                    .take_while(|node| seen.insert(Arc::as_ptr(node)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut rest: Vec<ArcNode<T>> = last
+            .map(|last| {
+                std::iter::successors(Some(last), |node| lock(node).prev.upgrade())
+                    .take_while(|node| seen.insert(Arc::as_ptr(node)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        rest.reverse();
+        chain.append(&mut rest);
+
+        for (i, node) in chain.iter().enumerate() {
+            let mut lock = lock(node);
+            lock.parent = Arc::downgrade(self);
+            lock.prev = i
+                .checked_sub(1)
+                .map_or_else(Weak::new, |i| Arc::downgrade(&chain[i]));
+            lock.next = chain.get(i + 1).map_or_else(Weak::new, Arc::downgrade);
+        }
+        let mut tail = lock(&self.tail);
+        let mut head = lock(&self.head);
+        self.set_head(
+            &mut head,
+            chain.first().map_or_else(Weak::new, Arc::downgrade),
+        );
+        self.set_tail(
+            &mut tail,
+            chain.last().map_or_else(Weak::new, Arc::downgrade),
+        );
+        drop(head);
+        drop(tail);
+        self.len.store(chain.len(), Ordering::Relaxed);
+        self.notify_len();
+    }
+
+    /// Renders the links of the list, one node per line, for diagnosing corruption.
+    ///
+    /// Each line shows a node's address, then where its `prev` and `next` pointers lead: another
+    /// address, `none`, or `dangling` if the node they pointed to has been dropped. A node whose
+    /// parent is not this list is marked `foreign`. Nodes are only locked with `try_lock`, so a
+    /// node which is currently locked is shown as `locked`, and the walk stops there. The head and
+    /// tail pointers are likewise shown as `<locked>` if they are in use, and the nodes are only
+    /// walked if the head is available.
+    // lock order:
+    //  self.head, try_lock {}
+    //  self.tail, try_lock {}
+    //  each node, one at a time, try_lock {}
+    pub fn debug_topology(self: &Arc<Self>) -> String {
+        fn describe<T>(weak: &Weak<T>) -> String {
+            match weak.upgrade() {
+                Some(node) => format!("{:p}", Arc::as_ptr(&node)),
+                None if is_dangling(weak) => "dangling".to_owned(),
+                None => "none".to_owned(),
+            }
+        }
+
+        let mut out = String::new();
+        let head = crate::try_lock(&self.head).map(|head| head.clone());
+        let tail = crate::try_lock(&self.tail).map(|tail| tail.clone());
+        let describe_endpoint = |weak: &Option<WeakNode<T>>| {
+            weak.as_ref()
+                .map_or_else(|| "<locked>".to_owned(), describe)
+        };
+        out.push_str(&format!("head -> {}\n", describe_endpoint(&head)));
+        let mut seen = HashSet::new();
+        let mut node = head.and_then(|head| head.upgrade());
+        while let Some(this) = node {
+            let addr = Arc::as_ptr(&this);
+            if !seen.insert(addr) {
+                out.push_str(&format!("{addr:p}: cycle\n"));
+                break;
+            }
+            let Some(lock) = crate::try_lock(&this) else {
+                out.push_str(&format!("{addr:p}: locked\n"));
+                break;
+            };
+            let parent = if lock.parent_is(self) { "" } else { " foreign" };
+            out.push_str(&format!(
+                "{} <- {addr:p} -> {}{parent}\n",
+                describe(&lock.prev),
+                describe(&lock.next),
+            ));
+            node = lock.next.upgrade();
+        }
+        out.push_str(&format!("tail -> {}\n", describe_endpoint(&tail)));
+        out
+    }
+}